@@ -1,29 +1,582 @@
-use std::env;
-use std::fs::File;
-use std::io::BufRead;
-use std::io::BufReader;
-use std::string::String;
+use std::cmp::Ordering;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt;
+use std::iter;
+use std::slice;
+use std::str::FromStr;
 
-fn main() {
-    let filename = env::args().nth(1).expect("Missing filename argument");
-    let file = File::open(filename).expect("File not found");
-    let lines = BufReader::new(file)
-        .lines()
+use aoc::args::Args;
+use aoc::output::csv_field;
+use aoc::output::export;
+use aoc::output::fail;
+use aoc::output::json_string;
+
+#[cfg(feature = "embed-inputs")]
+const INPUT: Option<&str> = Some(include_str!("../../01_input.txt"));
+#[cfg(not(feature = "embed-inputs"))]
+const INPUT: Option<&str> = None;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Elf {
+    file: usize,
+    index: usize,
+    items: usize,
+    calories: u64,
+    largest_item: u64,
+}
+
+impl Elf {
+    fn empty(file: usize, index: usize) -> Self {
+        Self {
+            file,
+            index,
+            items: 0,
+            calories: 0,
+            largest_item: 0,
+        }
+    }
+}
+
+/// What a blank line means when it does not directly follow an item, i.e.
+/// runs of several blank lines and blank lines at the start or end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Blanks {
+    /// Extra blank lines are ignored.
+    Collapse,
+    /// Every extra blank line closes an elf carrying nothing.
+    EmptyElves,
+    /// Extra blank lines are an error.
+    Reject,
+}
+
+impl FromStr for Blanks {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "collapse" => Ok(Blanks::Collapse),
+            "empty" => Ok(Blanks::EmptyElves),
+            "reject" => Ok(Blanks::Reject),
+            _ => Err(format!("unknown blank line policy {s:?}")),
+        }
+    }
+}
+
+/// How the block parser treats irregular input. Line endings, `\n` or
+/// `\r\n`, are already gone from the lines it is given.
+#[derive(Debug, Clone, Copy)]
+struct Policy {
+    /// Ignore whitespace around items, so whitespace-only lines are blank.
+    trim: bool,
+    blanks: Blanks,
+}
+
+impl Policy {
+    fn lenient() -> Self {
+        Self {
+            trim: true,
+            blanks: Blanks::Collapse,
+        }
+    }
+
+    fn strict() -> Self {
+        Self {
+            trim: false,
+            blanks: Blanks::Reject,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum ParseError {
+    InvalidItem { line: usize, text: String },
+    UnexpectedBlank { line: usize },
+    Overflow { line: usize },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::InvalidItem { line, text } => {
+                write!(f, "line {line}: invalid calorie count {text:?}")
+            }
+            ParseError::UnexpectedBlank { line } => write!(f, "line {line}: unexpected blank line"),
+            ParseError::Overflow { line } => write!(f, "line {line}: calorie total overflows"),
+        }
+    }
+}
+
+/// Streams the blank line separated blocks of the input as elves.
+struct Elves<'a> {
+    file: usize,
+    lines: iter::Enumerate<slice::Iter<'a, String>>,
+    policy: Policy,
+    index: usize,
+    done: bool,
+}
+
+impl<'a> Elves<'a> {
+    fn new(file: usize, lines: &'a [String], policy: Policy) -> Self {
+        Self {
+            file,
+            lines: lines.iter().enumerate(),
+            policy,
+            index: 0,
+            done: false,
+        }
+    }
+
+    fn elf(&mut self, elf: Elf) -> Option<Result<Elf, ParseError>> {
+        self.index += 1;
+        Some(Ok(elf))
+    }
+
+    fn fail(&mut self, error: ParseError) -> Option<Result<Elf, ParseError>> {
+        self.done = true;
+        Some(Err(error))
+    }
+}
+
+impl Iterator for Elves<'_> {
+    type Item = Result<Elf, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let mut current: Option<Elf> = None;
+        loop {
+            let Some((idx, line)) = self.lines.next() else {
+                self.done = true;
+                return current.and_then(|elf| self.elf(elf));
+            };
+            let line_no = idx + 1;
+            let text = if self.policy.trim {
+                line.trim()
+            } else {
+                line.as_str()
+            };
+            if text.is_empty() {
+                if let Some(elf) = current {
+                    return self.elf(elf);
+                }
+                match self.policy.blanks {
+                    Blanks::Collapse => continue,
+                    Blanks::EmptyElves => {
+                        let (file, index) = (self.file, self.index);
+                        return self.elf(Elf::empty(file, index));
+                    }
+                    Blanks::Reject => {
+                        return self.fail(ParseError::UnexpectedBlank { line: line_no })
+                    }
+                }
+            }
+            let Ok(item) = text.parse::<u64>() else {
+                return self.fail(ParseError::InvalidItem {
+                    line: line_no,
+                    text: line.clone(),
+                });
+            };
+            let elf = current.get_or_insert(Elf::empty(self.file, self.index));
+            let Some(calories) = elf.calories.checked_add(item) else {
+                return self.fail(ParseError::Overflow { line: line_no });
+            };
+            elf.items += 1;
+            elf.calories = calories;
+            elf.largest_item = elf.largest_item.max(item);
+        }
+    }
+}
+
+/// Orders elves by calories; on equal totals the elf that came first (by
+/// file, then position) is the greater one.
+#[derive(PartialEq, Eq)]
+struct ByCalories(Elf);
+
+impl Ord for ByCalories {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let key = |elf: &Elf| (elf.calories, Reverse((elf.file, elf.index)));
+        key(&self.0).cmp(&key(&other.0))
+    }
+}
+
+impl PartialOrd for ByCalories {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Keeps the `k` elves carrying the most calories while elves are streamed
+/// in.
+struct TopK {
+    k: usize,
+    heap: BinaryHeap<Reverse<ByCalories>>,
+}
+
+impl TopK {
+    fn new(k: usize) -> Self {
+        Self {
+            k,
+            heap: BinaryHeap::with_capacity(k + 1),
+        }
+    }
+
+    fn push(&mut self, elf: Elf) {
+        self.heap.push(Reverse(ByCalories(elf)));
+        if self.heap.len() > self.k {
+            self.heap.pop();
+        }
+    }
+
+    /// The kept elves, most calories first.
+    fn into_sorted(self) -> Vec<Elf> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(ByCalories(elf))| elf)
+            .collect()
+    }
+}
+
+fn top_elves<E>(elves: impl IntoIterator<Item = Result<Elf, E>>, k: usize) -> Result<Vec<Elf>, E> {
+    let mut top = TopK::new(k);
+    for elf in elves {
+        top.push(elf?);
+    }
+    Ok(top.into_sorted())
+}
+
+/// Linearly interpolated percentile `p` (0..=100) of sorted values.
+fn percentile(sorted: &[u64], p: f64) -> f64 {
+    let rank = p / 100.0 * (sorted.len() - 1) as f64;
+    let (low, high) = (rank.floor() as usize, rank.ceil() as usize);
+    let fraction = rank - low as f64;
+    sorted[low] as f64 + (sorted[high] as f64 - sorted[low] as f64) * fraction
+}
+
+fn report(elves: &[Elf], names: &[String]) {
+    if elves.is_empty() {
+        return;
+    }
+    let mut totals = elves.iter().map(|x| x.calories).collect::<Vec<_>>();
+    totals.sort();
+    let total = totals.iter().sum::<u64>();
+    let items = elves.iter().map(|x| x.items).sum::<usize>();
+    println!("elves {}", elves.len());
+    println!("total calories {total}");
+    println!("mean calories {:.1}", total as f64 / elves.len() as f64);
+    println!("median calories {:.1}", percentile(&totals, 50.0));
+    for p in [10.0, 25.0, 75.0, 90.0, 99.0] {
+        println!("p{p} calories {:.1}", percentile(&totals, p));
+    }
+    println!(
+        "items per elf min {} mean {:.1} max {}",
+        elves.iter().map(|x| x.items).min().unwrap(),
+        items as f64 / elves.len() as f64,
+        elves.iter().map(|x| x.items).max().unwrap()
+    );
+    let largest = elves
+        .iter()
+        .max_by_key(|x| (x.largest_item, Reverse((x.file, x.index))))
+        .unwrap();
+    println!(
+        "largest single item {} ({})",
+        largest.largest_item,
+        label(largest, names)
+    );
+}
+
+/// Names elves as `elf N`, prefixed with their file when several inventories
+/// are compared.
+fn label(elf: &Elf, names: &[String]) -> String {
+    if names.len() > 1 {
+        format!("{} elf {}", names[elf.file], elf.index + 1)
+    } else {
+        format!("elf {}", elf.index + 1)
+    }
+}
+
+fn print_top(top: &[Elf], k: usize, names: &[String]) {
+    if top.is_empty() {
+        println!("no elves");
+        return;
+    }
+    let total = top.iter().map(|x| x.calories).sum::<u64>();
+    println!("max calories {}", top[0].calories);
+    if top.len() < k {
+        println!("top {k} calories {total} (only {} elves)", top.len());
+    } else {
+        println!("top {k} calories {total}");
+    }
+    for (rank, elf) in top.iter().enumerate() {
+        println!("#{} {}: {}", rank + 1, label(elf, names), elf.calories);
+    }
+}
+
+/// Heaviest elf over all inventories and the calorie distribution of each.
+fn compare(files: &[Vec<Elf>], names: &[String]) {
+    if let Some(heaviest) = files
+        .iter()
         .flatten()
-        .collect::<Vec<String>>();
-    let mut calories = Vec::<u64>::new();
-    let mut current_calories = 0u64;
-    for line in &lines {
-        if line.is_empty() {
-            calories.push(current_calories);
-            current_calories = 0;
+        .max_by_key(|x| (x.calories, Reverse((x.file, x.index))))
+    {
+        println!(
+            "heaviest elf {}: {}",
+            label(heaviest, names),
+            heaviest.calories
+        );
+    }
+    let width = names.iter().map(|x| x.len()).max().unwrap_or(0).max(4);
+    println!(
+        "{:<width$} {:>6} {:>10} {:>10} {:>10} {:>10} {:>10}",
+        "file", "elves", "total", "mean", "median", "p90", "max"
+    );
+    for (name, elves) in names.iter().zip(files) {
+        let mut totals = elves.iter().map(|x| x.calories).collect::<Vec<_>>();
+        totals.sort();
+        let total = totals.iter().sum::<u64>();
+        if totals.is_empty() {
+            println!("{name:<width$} {:>6} {total:>10}", 0);
             continue;
         }
-        current_calories += line.parse::<u64>().unwrap();
+        println!(
+            "{name:<width$} {:>6} {total:>10} {:>10.1} {:>10.1} {:>10.1} {:>10}",
+            totals.len(),
+            total as f64 / totals.len() as f64,
+            percentile(&totals, 50.0),
+            percentile(&totals, 90.0),
+            totals[totals.len() - 1]
+        );
+    }
+}
+
+fn to_csv(elves: &[Elf], names: &[String]) -> String {
+    let with_file = names.len() > 1;
+    let mut out = if with_file {
+        "file,elf,items,calories\n".to_string()
+    } else {
+        "elf,items,calories\n".to_string()
+    };
+    for elf in elves {
+        if with_file {
+            out += &format!("{},", csv_field(&names[elf.file]));
+        }
+        out += &format!("{},{},{}\n", elf.index + 1, elf.items, elf.calories);
+    }
+    out
+}
+
+fn to_json(elves: &[Elf], names: &[String]) -> String {
+    let rows = elves
+        .iter()
+        .map(|elf| {
+            let file = if names.len() > 1 {
+                format!("\"file\": {}, ", json_string(&names[elf.file]))
+            } else {
+                String::new()
+            };
+            format!(
+                "  {{{file}\"elf\": {}, \"items\": {}, \"calories\": {}}}",
+                elf.index + 1,
+                elf.items,
+                elf.calories
+            )
+        })
+        .collect::<Vec<_>>();
+    format!("[\n{}\n]\n", rows.join(",\n"))
+}
+
+fn main() {
+    let _stats = aoc::stats::day();
+    let args = Args::from_env();
+    let k = args.parsed("top", 3usize);
+    if k == 0 {
+        fail("--top must be at least 1");
+    }
+    let mut policy = if args.flag("strict") {
+        Policy::strict()
+    } else {
+        Policy::lenient()
+    };
+    policy.blanks = args.parsed("blanks", policy.blanks);
+    let (csv, json) = (args.value("csv"), args.value("json"));
+    let inputs = aoc::stats::phase("read", || aoc::input::inputs_from(&args, INPUT));
+    let names = inputs
+        .iter()
+        .map(|(name, _)| name.clone())
+        .collect::<Vec<_>>();
+    let parse = |file: usize| {
+        Elves::new(file, &inputs[file].1, policy).map(move |x| x.map_err(|e| (file, e)))
+    };
+    let collect = inputs.len() > 1 || args.flag("report") || csv.is_some() || json.is_some();
+    let result = aoc::stats::phase("parse", || {
+        if collect {
+            (0..inputs.len())
+                .map(|file| parse(file).collect::<Result<Vec<_>, _>>())
+                .collect::<Result<Vec<_>, _>>()
+        } else {
+            Ok(Vec::new())
+        }
+    })
+    .and_then(|files| {
+        let top = aoc::stats::phase("top", || {
+            if collect {
+                top_elves(files.iter().flatten().copied().map(Ok), k)
+            } else {
+                top_elves(parse(0), k)
+            }
+        })?;
+        Ok((files, top))
+    });
+    let (files, top) = result.unwrap_or_else(|(file, e)| fail(format!("{}: {e}", names[file])));
+    if files.len() > 1 {
+        for (name, elves) in names.iter().zip(&files) {
+            println!("== {name}");
+            let top = top_elves(elves.iter().copied().map(Ok::<_, ()>), k).unwrap();
+            print_top(&top, k, &names);
+        }
+        println!("== all files");
+    }
+    print_top(&top, k, &names);
+    if files.len() > 1 {
+        println!("== comparison");
+        compare(&files, &names);
+    }
+    let elves = files.concat();
+    if args.flag("report") {
+        report(&elves, &names);
+    }
+    if let Some(path) = csv {
+        export(path, to_csv(&elves, &names));
+    }
+    if let Some(path) = json {
+        export(path, to_json(&elves, &names));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn elf(file: usize, index: usize, calories: u64) -> Elf {
+        Elf {
+            calories,
+            ..Elf::empty(file, index)
+        }
+    }
+
+    /// Items and calories of the elves in `text`, split on `\n` only.
+    fn parse(text: &str, policy: Policy) -> Result<Vec<(usize, u64)>, ParseError> {
+        let lines = text.split('\n').map(str::to_string).collect::<Vec<_>>();
+        Elves::new(0, &lines, policy)
+            .map(|x| x.map(|elf| (elf.items, elf.calories)))
+            .collect()
+    }
+
+    fn with_blanks(blanks: Blanks) -> Policy {
+        Policy {
+            blanks,
+            ..Policy::lenient()
+        }
+    }
+
+    #[test]
+    fn collapse_ignores_extra_blank_lines() {
+        let elves = parse("\n1\n2\n\n\n3\n", with_blanks(Blanks::Collapse));
+        assert_eq!(elves.unwrap(), [(2, 3), (1, 3)]);
+    }
+
+    #[test]
+    fn extra_blank_lines_can_be_empty_elves() {
+        let lines = ["", "1", "2", "", "", "3", ""].map(str::to_string);
+        let elves = Elves::new(0, &lines, with_blanks(Blanks::EmptyElves))
+            .map(|x| x.map(|elf| (elf.index, elf.items, elf.calories)))
+            .collect::<Result<Vec<_>, _>>();
+        assert_eq!(elves.unwrap(), [(0, 0, 0), (1, 2, 3), (2, 0, 0), (3, 1, 3)]);
+    }
+
+    #[test]
+    fn reject_names_the_extra_blank_line() {
+        let reject = with_blanks(Blanks::Reject);
+        assert_eq!(parse("1\n\n2", reject).unwrap(), [(1, 1), (1, 2)]);
+        assert_eq!(
+            parse("1\n\n\n2", reject),
+            Err(ParseError::UnexpectedBlank { line: 3 })
+        );
+        assert_eq!(
+            parse("\n1", reject),
+            Err(ParseError::UnexpectedBlank { line: 1 })
+        );
+    }
+
+    #[test]
+    fn lenient_trims_and_strict_does_not() {
+        let text = " 1 \n \t\n2\r";
+        assert_eq!(parse(text, Policy::lenient()).unwrap(), [(1, 1), (1, 2)]);
+        assert_eq!(
+            parse(text, Policy::strict()),
+            Err(ParseError::InvalidItem {
+                line: 1,
+                text: " 1 ".to_string()
+            })
+        );
+        assert_eq!(
+            parse("1\n \n2", Policy::strict()),
+            Err(ParseError::InvalidItem {
+                line: 2,
+                text: " ".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn errors_name_the_line_and_end_the_stream() {
+        assert_eq!(
+            parse("1\n\n2\nx3", Policy::lenient()),
+            Err(ParseError::InvalidItem {
+                line: 4,
+                text: "x3".to_string()
+            })
+        );
+        let lines = ["1", "", &u64::MAX.to_string(), "1", "2"].map(str::to_string);
+        let mut elves = Elves::new(0, &lines, Policy::strict());
+        assert!(elves.next().unwrap().is_ok());
+        assert_eq!(elves.next(), Some(Err(ParseError::Overflow { line: 4 })));
+        assert_eq!(elves.next(), None);
+    }
+
+    fn top(elves: &[Elf], k: usize) -> Vec<(usize, usize, u64)> {
+        top_elves(elves.iter().copied().map(Ok::<_, ()>), k)
+            .unwrap()
+            .iter()
+            .map(|x| (x.file, x.index, x.calories))
+            .collect()
+    }
+
+    #[test]
+    fn top_k_keeps_the_most_calories_first() {
+        let elves = [elf(0, 0, 5), elf(0, 1, 9), elf(0, 2, 1), elf(0, 3, 7)];
+        assert_eq!(top(&elves, 2), [(0, 1, 9), (0, 3, 7)]);
+        assert_eq!(top(&elves, 3), [(0, 1, 9), (0, 3, 7), (0, 0, 5)]);
+    }
+
+    #[test]
+    fn top_k_breaks_ties_by_position() {
+        let elves = [elf(0, 0, 5), elf(1, 0, 5), elf(0, 2, 5), elf(0, 1, 5)];
+        assert_eq!(top(&elves, 3), [(0, 0, 5), (0, 1, 5), (0, 2, 5)]);
+        assert_eq!(top(&elves, 4)[3], (1, 0, 5));
+    }
+
+    #[test]
+    fn top_k_of_nothing() {
+        assert_eq!(top(&[elf(0, 0, 5)], 0), []);
+        assert_eq!(top(&[], 3), []);
+    }
+
+    #[test]
+    fn top_k_with_fewer_elves_than_k() {
+        let elves = [elf(0, 0, 2), elf(0, 1, 8)];
+        assert_eq!(top(&elves, 3), [(0, 1, 8), (0, 0, 2)]);
     }
-    calories.push(current_calories);
-    calories.sort_by(|a, b| b.cmp(a));
-    let top_three = calories[0] + calories[1] + calories[2];
-    println!("max calories {}", calories[0]);
-    println!("top 3 calories {}", top_three);
 }
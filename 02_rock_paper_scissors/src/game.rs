@@ -0,0 +1,331 @@
+//! Rules of cyclic hand games such as Rock-Paper-Scissors.
+//!
+//! The gestures of a game form a cycle of odd length `n`; every gesture beats
+//! the `(n - 1) / 2` gestures before it and loses to the ones after it.
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Gesture(pub usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    Lose,
+    Draw,
+    Win,
+}
+
+impl Outcome {
+    pub const ALL: [Outcome; 3] = [Outcome::Lose, Outcome::Draw, Outcome::Win];
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Outcome::Lose => "lose",
+            Outcome::Draw => "draw",
+            Outcome::Win => "win",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Points for a round: the shape you played plus the outcome.
+#[derive(Debug, Clone)]
+pub struct Scoring {
+    pub shapes: Vec<i32>,
+    pub lose: i32,
+    pub draw: i32,
+    pub win: i32,
+}
+
+impl Scoring {
+    /// Shapes worth 1, 2, 3, ... in cycle order and 0/3/6 for the outcome.
+    pub fn standard(gestures: usize) -> Self {
+        Self {
+            shapes: (1..=gestures as i32).collect(),
+            lose: 0,
+            draw: 3,
+            win: 6,
+        }
+    }
+
+    pub fn outcome(&self, outcome: Outcome) -> i32 {
+        match outcome {
+            Outcome::Lose => self.lose,
+            Outcome::Draw => self.draw,
+            Outcome::Win => self.win,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Game {
+    pub names: Vec<String>,
+    /// Symbols for the opponent's gestures in the first column of the guide.
+    pub opponent_symbols: Vec<char>,
+    /// Symbols of the second column when it names your gesture.
+    pub gesture_symbols: Vec<char>,
+    /// Symbols of the second column when it names the outcome, in
+    /// lose/draw/win order.
+    pub outcome_symbols: Vec<char>,
+    pub scoring: Scoring,
+}
+
+impl Game {
+    pub fn classic() -> Self {
+        Self {
+            names: ["Rock", "Paper", "Scissors"].map(String::from).to_vec(),
+            opponent_symbols: "ABC".chars().collect(),
+            gesture_symbols: "XYZ".chars().collect(),
+            outcome_symbols: "XYZ".chars().collect(),
+            scoring: Scoring::standard(3),
+        }
+    }
+
+    /// Rock-Paper-Scissors-Lizard-Spock, ordered so that the cycle rule
+    /// gives the usual dominance.
+    pub fn rpsls() -> Self {
+        Self {
+            names: ["Rock", "Spock", "Paper", "Lizard", "Scissors"]
+                .map(String::from)
+                .to_vec(),
+            opponent_symbols: "ABCDE".chars().collect(),
+            gesture_symbols: "VWXYZ".chars().collect(),
+            outcome_symbols: "XYZ".chars().collect(),
+            scoring: Scoring::standard(5),
+        }
+    }
+
+    pub fn by_name(name: &str) -> Result<Self, String> {
+        match name {
+            "classic" => Ok(Self::classic()),
+            "rpsls" => Ok(Self::rpsls()),
+            _ => Err(format!("unknown game {name:?}")),
+        }
+    }
+
+    /// Checks that the definition describes a playable game.
+    pub fn validate(&self) -> Result<(), String> {
+        let n = self.names.len();
+        if n < 3 || n.is_multiple_of(2) {
+            return Err(format!(
+                "a cyclic game needs an odd number (>= 3) of gestures, got {n}"
+            ));
+        }
+        let lists = [
+            ("opponent symbols", &self.opponent_symbols, n),
+            ("gesture symbols", &self.gesture_symbols, n),
+            ("outcome symbols", &self.outcome_symbols, 3),
+        ];
+        for (what, symbols, expected) in lists {
+            if symbols.len() != expected {
+                return Err(format!("expected {expected} {what}, got {}", symbols.len()));
+            }
+            if let Some(dup) = symbols
+                .iter()
+                .enumerate()
+                .find(|(i, x)| symbols[..*i].contains(x))
+            {
+                return Err(format!("duplicate {what} {:?}", dup.1));
+            }
+        }
+        if self.scoring.shapes.len() != n {
+            return Err(format!(
+                "expected {n} shape scores, got {}",
+                self.scoring.shapes.len()
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn gestures(&self) -> impl Iterator<Item = Gesture> {
+        (0..self.names.len()).map(Gesture)
+    }
+
+    pub fn name(&self, gesture: Gesture) -> &str {
+        &self.names[gesture.0]
+    }
+
+    pub fn opponent(&self, symbol: char) -> Option<Gesture> {
+        self.opponent_symbols
+            .iter()
+            .position(|&x| x == symbol)
+            .map(Gesture)
+    }
+
+    pub fn gesture(&self, symbol: char) -> Option<Gesture> {
+        self.gesture_symbols
+            .iter()
+            .position(|&x| x == symbol)
+            .map(Gesture)
+    }
+
+    pub fn outcome_symbol(&self, symbol: char) -> Option<Outcome> {
+        self.outcome_symbols
+            .iter()
+            .position(|&x| x == symbol)
+            .map(|i| Outcome::ALL[i])
+    }
+
+    pub fn beats(&self, a: Gesture, b: Gesture) -> bool {
+        let n = self.names.len();
+        let distance = (a.0 + n - b.0) % n;
+        distance != 0 && distance <= n / 2
+    }
+
+    /// The outcome of `you` playing against `opponent`.
+    pub fn outcome(&self, you: Gesture, opponent: Gesture) -> Outcome {
+        if you == opponent {
+            Outcome::Draw
+        } else if self.beats(you, opponent) {
+            Outcome::Win
+        } else {
+            Outcome::Lose
+        }
+    }
+
+    /// A gesture achieving `outcome` against `opponent`: the closest one in
+    /// the cycle when several would do.
+    pub fn respond(&self, opponent: Gesture, outcome: Outcome) -> Gesture {
+        let n = self.names.len();
+        match outcome {
+            Outcome::Draw => opponent,
+            Outcome::Win => Gesture((opponent.0 + 1) % n),
+            Outcome::Lose => Gesture((opponent.0 + n - 1) % n),
+        }
+    }
+
+    pub fn score(&self, you: Gesture, opponent: Gesture) -> i32 {
+        self.scoring.shapes[you.0] + self.scoring.outcome(self.outcome(you, opponent))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn by_name(game: &Game, name: &str) -> Gesture {
+        game.gestures().find(|x| game.name(*x) == name).unwrap()
+    }
+
+    /// Checks that `wins` are exactly the pairs where the first gesture
+    /// beats the second.
+    fn assert_dominance(game: &Game, wins: &[(&str, &str)]) {
+        for a in game.gestures() {
+            for b in game.gestures() {
+                let expected = wins.contains(&(game.name(a), game.name(b)));
+                assert_eq!(game.beats(a, b), expected, "{a:?} beats {b:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn classic_dominance() {
+        let game = Game::classic();
+        assert_dominance(
+            &game,
+            &[
+                ("Rock", "Scissors"),
+                ("Paper", "Rock"),
+                ("Scissors", "Paper"),
+            ],
+        );
+        let (rock, paper) = (by_name(&game, "Rock"), by_name(&game, "Paper"));
+        assert_eq!(game.outcome(rock, paper), Outcome::Lose);
+        assert_eq!(game.outcome(paper, rock), Outcome::Win);
+        assert_eq!(game.outcome(rock, rock), Outcome::Draw);
+        assert_eq!(game.score(paper, rock), 8);
+        assert_eq!(game.score(rock, paper), 1);
+    }
+
+    #[test]
+    fn rpsls_dominance() {
+        assert_dominance(
+            &Game::rpsls(),
+            &[
+                ("Scissors", "Paper"),
+                ("Paper", "Rock"),
+                ("Rock", "Lizard"),
+                ("Lizard", "Spock"),
+                ("Spock", "Scissors"),
+                ("Scissors", "Lizard"),
+                ("Lizard", "Paper"),
+                ("Paper", "Spock"),
+                ("Spock", "Rock"),
+                ("Rock", "Scissors"),
+            ],
+        );
+    }
+
+    #[test]
+    fn respond_achieves_every_outcome() {
+        for game in [Game::classic(), Game::rpsls()] {
+            for opponent in game.gestures() {
+                for outcome in Outcome::ALL {
+                    let you = game.respond(opponent, outcome);
+                    assert_eq!(game.outcome(you, opponent), outcome);
+                }
+            }
+        }
+        let game = Game::classic();
+        let rock = by_name(&game, "Rock");
+        let respond = |outcome| game.name(game.respond(rock, outcome));
+        assert_eq!(respond(Outcome::Lose), "Scissors");
+        assert_eq!(respond(Outcome::Draw), "Rock");
+        assert_eq!(respond(Outcome::Win), "Paper");
+    }
+
+    #[test]
+    fn validate_accepts_the_built_in_games() {
+        assert_eq!(Game::classic().validate(), Ok(()));
+        assert_eq!(Game::rpsls().validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_rejects_an_even_gesture_count() {
+        let mut game = Game::classic();
+        game.names.push("Well".to_string());
+        game.opponent_symbols.push('D');
+        game.gesture_symbols.push('W');
+        game.scoring = Scoring::standard(4);
+        assert_eq!(
+            game.validate(),
+            Err("a cyclic game needs an odd number (>= 3) of gestures, got 4".to_string())
+        );
+        game.names.truncate(1);
+        assert!(game.validate().unwrap_err().ends_with("got 1"));
+    }
+
+    #[test]
+    fn validate_rejects_duplicate_symbols() {
+        let mut game = Game::classic();
+        game.opponent_symbols = vec!['A', 'B', 'A'];
+        assert_eq!(
+            game.validate(),
+            Err("duplicate opponent symbols 'A'".to_string())
+        );
+        let mut game = Game::rpsls();
+        game.outcome_symbols = vec!['X', 'Y', 'Y'];
+        assert_eq!(
+            game.validate(),
+            Err("duplicate outcome symbols 'Y'".to_string())
+        );
+    }
+
+    #[test]
+    fn validate_rejects_a_wrong_number_of_symbols_or_shape_scores() {
+        let mut game = Game::classic();
+        game.gesture_symbols.pop();
+        assert_eq!(
+            game.validate(),
+            Err("expected 3 gesture symbols, got 2".to_string())
+        );
+        let mut game = Game::rpsls();
+        game.scoring = Scoring::standard(3);
+        assert_eq!(
+            game.validate(),
+            Err("expected 5 shape scores, got 3".to_string())
+        );
+    }
+}
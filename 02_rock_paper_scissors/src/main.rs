@@ -1,97 +1,496 @@
-use std::env;
-use std::fs::File;
-use std::io::BufRead;
-use std::io::BufReader;
-
-#[derive(Clone, Copy)]
-enum Gesture {
-    Rock = 1,
-    Paper = 2,
-    Scissors = 3,
-}
-
-impl Gesture {
-    fn from_char(input: char) -> Result<Gesture, ()> {
-        match input {
-            'A' => Ok(Gesture::Rock),
-            'B' => Ok(Gesture::Paper),
-            'C' => Ok(Gesture::Scissors),
-            _ => Err(()),
-        }
+use std::str::FromStr;
+
+use aoc::args::Args;
+use aoc::output::csv_field;
+use aoc::output::export;
+use aoc::output::fail;
+use aoc::rng::Rng;
+use game::Game;
+use game::Gesture;
+use game::Outcome;
+use game::Scoring;
+use tournament::Strategy;
+
+mod game;
+mod solver;
+mod tournament;
+
+#[cfg(feature = "embed-inputs")]
+const INPUT: Option<&str> = Some(include_str!("../../02_input.txt"));
+#[cfg(not(feature = "embed-inputs"))]
+const INPUT: Option<&str> = None;
+
+struct Play {
+    line: usize,
+    opponent: Gesture,
+    you: char,
+}
+
+/// Turns the second column of the guide into the gesture you play.
+trait Decoder {
+    fn name(&self) -> &'static str;
+
+    fn decode(&self, game: &Game, play: &Play) -> Option<Gesture>;
+}
+
+/// Part 1: the second column names your gesture (X, Y and Z are Rock, Paper
+/// and Scissors in the classic game).
+struct AsGesture;
+
+impl Decoder for AsGesture {
+    fn name(&self) -> &'static str {
+        "gestures"
     }
 
-    fn draw(&self) -> Gesture {
-        *self
+    fn decode(&self, game: &Game, play: &Play) -> Option<Gesture> {
+        game.gesture(play.you)
     }
+}
 
-    fn win(&self) -> Gesture {
-        match self {
-            Gesture::Rock => Gesture::Paper,
-            Gesture::Paper => Gesture::Scissors,
-            Gesture::Scissors => Gesture::Rock,
-        }
+/// Part 2: the second column says whether to lose, draw or win the round.
+struct AsOutcome;
+
+impl Decoder for AsOutcome {
+    fn name(&self) -> &'static str {
+        "outcomes"
     }
 
-    fn lose(&self) -> Gesture {
-        match self {
-            Gesture::Rock => Gesture::Scissors,
-            Gesture::Paper => Gesture::Rock,
-            Gesture::Scissors => Gesture::Paper,
-        }
+    fn decode(&self, game: &Game, play: &Play) -> Option<Gesture> {
+        let outcome = game.outcome_symbol(play.you)?;
+        Some(game.respond(play.opponent, outcome))
     }
 }
 
-struct Play {
+/// How one line of the guide plays out.
+struct Round {
+    line: usize,
     opponent: Gesture,
-    you: char,
+    you: Gesture,
+    outcome: Outcome,
+    shape_score: i32,
+    outcome_score: i32,
+}
+
+impl Round {
+    fn score(&self) -> i32 {
+        self.shape_score + self.outcome_score
+    }
+}
+
+fn breakdown(game: &Game, plays: &[Play], decoder: &dyn Decoder) -> Result<Vec<Round>, String> {
+    plays
+        .iter()
+        .map(|item| {
+            let you = decoder.decode(game, item).ok_or_else(|| {
+                format!(
+                    "line {}: {:?} does not name one of the {}",
+                    item.line,
+                    item.you,
+                    decoder.name()
+                )
+            })?;
+            let outcome = game.outcome(you, item.opponent);
+            Ok(Round {
+                line: item.line,
+                opponent: item.opponent,
+                you,
+                outcome,
+                shape_score: game.scoring.shapes[you.0],
+                outcome_score: game.scoring.outcome(outcome),
+            })
+        })
+        .collect()
+}
+
+fn score(game: &Game, plays: &[Play], decoder: &dyn Decoder) -> Result<i32, String> {
+    Ok(breakdown(game, plays, decoder)?
+        .iter()
+        .map(Round::score)
+        .sum())
+}
+
+fn print_rounds(game: &Game, rounds: &[Round]) {
+    for round in rounds {
+        println!(
+            "line {}: {} vs {}, {}, {} + {} = {}",
+            round.line,
+            game.name(round.you),
+            game.name(round.opponent),
+            round.outcome,
+            round.shape_score,
+            round.outcome_score,
+            round.score()
+        );
+    }
+}
+
+/// Counts of each outcome and of each gesture you played.
+fn histograms(game: &Game, rounds: &[Round]) -> Vec<(&'static str, String, usize)> {
+    let mut rows = Vec::new();
+    for outcome in Outcome::ALL {
+        let count = rounds.iter().filter(|x| x.outcome == outcome).count();
+        rows.push(("outcome", outcome.to_string(), count));
+    }
+    for gesture in game.gestures() {
+        let count = rounds.iter().filter(|x| x.you == gesture).count();
+        rows.push(("gesture", game.name(gesture).to_string(), count));
+    }
+    rows
+}
+
+fn print_histograms(rows: &[(&str, String, usize)], total: usize) {
+    let width = rows
+        .iter()
+        .map(|(_, name, _)| name.len())
+        .max()
+        .unwrap_or(0);
+    let mut kind = "";
+    for (row_kind, name, count) in rows {
+        if *row_kind != kind {
+            kind = row_kind;
+            println!("{kind}s:");
+        }
+        let share = *count as f64 / total.max(1) as f64;
+        println!(
+            "  {name:<width$} {count:>6} {:>5.1}% {}",
+            100.0 * share,
+            "#".repeat((share * 40.0).round() as usize)
+        );
+    }
+}
+
+fn rounds_csv(game: &Game, rounds: &[Round]) -> String {
+    let mut out = "line,opponent,you,outcome,shape_score,outcome_score,score\n".to_string();
+    for round in rounds {
+        out += &format!(
+            "{},{},{},{},{},{},{}\n",
+            round.line,
+            csv_field(game.name(round.opponent)),
+            csv_field(game.name(round.you)),
+            round.outcome,
+            round.shape_score,
+            round.outcome_score,
+            round.score()
+        );
+    }
+    out
+}
+
+fn histograms_csv(rows: &[(&str, String, usize)]) -> String {
+    let mut out = "kind,name,count\n".to_string();
+    for (kind, name, count) in rows {
+        out += &format!("{kind},{},{count}\n", csv_field(name));
+    }
+    out
+}
+
+fn parse_plays(game: &Game, lines: &[String]) -> Result<Vec<Play>, String> {
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| {
+            let symbols = line.split_whitespace().collect::<Vec<_>>();
+            let [opponent, you] = symbols[..] else {
+                return Err(format!(
+                    "line {}: expected two symbols in {line:?}",
+                    idx + 1
+                ));
+            };
+            let mut opponent_chars = opponent.chars();
+            let mut you_chars = you.chars();
+            let (Some(opponent), None, Some(you), None) = (
+                opponent_chars.next(),
+                opponent_chars.next(),
+                you_chars.next(),
+                you_chars.next(),
+            ) else {
+                return Err(format!(
+                    "line {}: symbols must be single characters in {line:?}",
+                    idx + 1
+                ));
+            };
+            let opponent = game
+                .opponent(opponent)
+                .ok_or_else(|| format!("line {}: unknown opponent symbol {opponent:?}", idx + 1))?;
+            if game.gesture(you).is_none() && game.outcome_symbol(you).is_none() {
+                return Err(format!("line {}: unknown guide symbol {you:?}", idx + 1));
+            }
+            Ok(Play {
+                line: idx + 1,
+                opponent,
+                you,
+            })
+        })
+        .collect()
 }
 
-fn score_play(you: Gesture, opponent: Gesture) -> i32 {
-    match you {
-        Gesture::Rock => match opponent {
-            Gesture::Rock => 3,
-            Gesture::Paper => 0,
-            Gesture::Scissors => 6,
-        },
-        Gesture::Paper => match opponent {
-            Gesture::Rock => 6,
-            Gesture::Paper => 3,
-            Gesture::Scissors => 0,
-        },
-        Gesture::Scissors => match opponent {
-            Gesture::Rock => 0,
-            Gesture::Paper => 6,
-            Gesture::Scissors => 3,
-        },
-    }
-}
-
-fn score(plays: &Vec<Play>) -> i32 {
-    plays.into_iter().fold(0i32, |acc, item| {
-        let your_gesture = match item.you {
-            'X' => item.opponent.lose(),
-            'Y' => item.opponent.draw(),
-            'Z' => item.opponent.win(),
-            _ => panic!("Should not happen :D"),
+fn split_list<T: FromStr>(what: &str, value: &str) -> Result<Vec<T>, String> {
+    value
+        .split(',')
+        .map(|x| {
+            x.trim()
+                .parse()
+                .map_err(|_| format!("invalid {what} {x:?}"))
+        })
+        .collect()
+}
+
+/// The game picked with `--game`, adjusted by the options overriding
+/// gesture names, guide symbols and scores.
+fn game_from_args(args: &Args) -> Result<Game, String> {
+    let mut game = Game::by_name(args.value("game").unwrap_or("classic"))?;
+    if let Some(names) = args.value("names") {
+        game.names = split_list("gesture name", names)?;
+        game.scoring.shapes = Scoring::standard(game.names.len()).shapes;
+    }
+    if let Some(symbols) = args.value("opponent-symbols") {
+        game.opponent_symbols = symbols.chars().collect();
+    }
+    if let Some(symbols) = args.value("gesture-symbols") {
+        game.gesture_symbols = symbols.chars().collect();
+    }
+    if let Some(symbols) = args.value("outcome-symbols") {
+        game.outcome_symbols = symbols.chars().collect();
+    }
+    if let Some(scores) = args.value("shape-scores") {
+        game.scoring.shapes = split_list("shape score", scores)?;
+    }
+    if let Some(scores) = args.value("outcome-scores") {
+        let [lose, draw, win] = split_list("outcome score", scores)?[..] else {
+            return Err("--outcome-scores needs three values: lose,draw,win".to_string());
         };
-        acc + score_play(your_gesture, item.opponent) + your_gesture as i32
+        (game.scoring.lose, game.scoring.draw, game.scoring.win) = (lose, draw, win);
+    }
+    game.validate()?;
+    Ok(game)
+}
+
+fn print_search(game: &Game, plays: &[Play]) {
+    let gestures = game.gestures().collect::<Vec<_>>();
+    let by_gesture = solver::search(game, plays, &gestures, |opponent, you| {
+        game.score(you, opponent)
+    })
+    .map(|x| {
+        (
+            x.best.map(|g| game.name(g)),
+            x.worst.map(|g| game.name(g)),
+            x.tried,
+        )
+    });
+    let by_outcome = solver::search(game, plays, &Outcome::ALL, |opponent, outcome| {
+        game.score(game.respond(opponent, outcome), opponent)
     })
+    .map(|x| (x.best, x.worst, x.tried));
+    match by_gesture {
+        Ok((best, worst, tried)) => {
+            println!("Mappings tried (gestures): {tried}");
+            println!("Best mapping (gestures): {best}");
+            println!("Worst mapping (gestures): {worst}");
+        }
+        Err(e) => println!("No mapping (gestures): {e}"),
+    }
+    match by_outcome {
+        Ok((best, worst, tried)) => {
+            println!("Mappings tried (outcomes): {tried}");
+            println!("Best mapping (outcomes): {best}");
+            println!("Worst mapping (outcomes): {worst}");
+        }
+        Err(e) => println!("No mapping (outcomes): {e}"),
+    }
+}
+
+fn print_tournament(game: &Game, plays: &[Play], rounds: usize, seed: u64) {
+    let mut strategies: Vec<Box<dyn Strategy>> = Vec::new();
+    let decoders: [&dyn Decoder; 2] = [&AsGesture, &AsOutcome];
+    for decoder in decoders {
+        let moves = plays
+            .iter()
+            .map(|x| decoder.decode(game, x))
+            .collect::<Option<Vec<_>>>();
+        if let Some(moves) = moves.filter(|x| !x.is_empty()) {
+            let name = format!("guide-{}", decoder.name());
+            strategies.push(Box::new(tournament::Fixed::new(&name, moves)));
+        }
+    }
+    strategies.push(Box::new(tournament::Random));
+    strategies.push(Box::new(tournament::FrequencyCounter::default()));
+    strategies.push(Box::new(tournament::BeatLast::default()));
+    strategies.push(Box::new(tournament::Cycle::default()));
+    let mut rng = Rng::new(seed);
+    let standings = tournament::round_robin(game, &mut strategies, rounds, &mut rng);
+    println!(
+        "Tournament: {} strategies, {rounds} rounds per match, seed {seed}",
+        standings.len()
+    );
+    println!(
+        "{:<4} {:<16} {:>10} {:>7} {:>7} {:>7}",
+        "rank", "strategy", "points", "win%", "draw%", "loss%"
+    );
+    for (rank, standing) in standings.iter().enumerate() {
+        let rate = |x: usize| 100.0 * x as f64 / standing.rounds().max(1) as f64;
+        println!(
+            "{:<4} {:<16} {:>10} {:>7.1} {:>7.1} {:>7.1}",
+            format!("#{}", rank + 1),
+            standing.name,
+            standing.points,
+            rate(standing.wins),
+            rate(standing.draws),
+            rate(standing.losses)
+        );
+    }
 }
 
 fn main() {
-    let filename = env::args().nth(1).expect("Missing filename argument");
-    let file = File::open(filename).expect("File not found");
-    let lines = BufReader::new(file)
-        .lines()
-        .flatten()
-        .collect::<Vec<String>>();
-    let plays: Vec<Play> = lines
-        .iter()
-        .map(|line| Play {
-            opponent: Gesture::from_char(line.chars().nth(0).unwrap()).unwrap(),
-            you: line.chars().nth(2).unwrap(),
-        })
-        .collect();
-    let score = score(&plays);
-    println!("Score: {}", score);
+    let _stats = aoc::stats::day();
+    let args = Args::from_env();
+    let game = game_from_args(&args).unwrap_or_else(|e| fail(e));
+    let lines = aoc::stats::phase("read", || aoc::input::lines_from(&args, INPUT));
+    let plays =
+        aoc::stats::phase("parse", || parse_plays(&game, &lines)).unwrap_or_else(|e| fail(e));
+    for (label, total) in [
+        (
+            "gestures",
+            aoc::stats::phase("gestures", || score(&game, &plays, &AsGesture)),
+        ),
+        (
+            "outcomes",
+            aoc::stats::phase("outcomes", || score(&game, &plays, &AsOutcome)),
+        ),
+    ] {
+        match total {
+            Ok(total) => println!("Score ({label}): {total}"),
+            Err(e) => println!("Score ({label}): not applicable, {e}"),
+        }
+    }
+    if args.flag("search") {
+        aoc::stats::phase("search", || print_search(&game, &plays));
+    }
+    if let Some(reading) = args.value("detail") {
+        let decoder: &dyn Decoder = match reading {
+            "gestures" => &AsGesture,
+            "outcomes" => &AsOutcome,
+            _ => fail(format!(
+                "--detail must be gestures or outcomes, not {reading:?}"
+            )),
+        };
+        let rounds = aoc::stats::phase("breakdown", || breakdown(&game, &plays, decoder))
+            .unwrap_or_else(|e| fail(e));
+        let rows = histograms(&game, &rounds);
+        println!("Breakdown ({reading}):");
+        print_rounds(&game, &rounds);
+        print_histograms(&rows, rounds.len());
+        if let Some(path) = args.value("csv") {
+            export(path, rounds_csv(&game, &rounds));
+        }
+        if let Some(path) = args.value("histogram-csv") {
+            export(path, histograms_csv(&rows));
+        }
+    }
+    if args.flag("tournament") {
+        let rounds = args.parsed("rounds", 1000usize);
+        let seed = args.parsed("seed", 2022u64);
+        aoc::stats::phase("tournament", || {
+            print_tournament(&game, &plays, rounds, seed)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    fn summary(game: &Game, rounds: &[Round]) -> Vec<(usize, String, String, i32)> {
+        rounds
+            .iter()
+            .map(|x| {
+                (
+                    x.line,
+                    game.name(x.you).to_string(),
+                    x.outcome.to_string(),
+                    x.score(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn breakdown_of_the_sample() {
+        let game = Game::classic();
+        let plays = parse_plays(&game, &lines("A Y\nB X\n\nC Z")).unwrap();
+        let rounds = breakdown(&game, &plays, &AsGesture).unwrap();
+        let row = |line, you: &str, outcome: &str, score| {
+            (line, you.to_string(), outcome.to_string(), score)
+        };
+        assert_eq!(
+            summary(&game, &rounds),
+            [
+                row(1, "Paper", "win", 8),
+                row(2, "Rock", "lose", 1),
+                row(4, "Scissors", "draw", 6)
+            ]
+        );
+        let rounds = breakdown(&game, &plays, &AsOutcome).unwrap();
+        assert_eq!(
+            summary(&game, &rounds),
+            [
+                row(1, "Rock", "draw", 4),
+                row(2, "Rock", "lose", 1),
+                row(4, "Rock", "win", 7)
+            ]
+        );
+    }
+
+    #[test]
+    fn breakdown_names_the_line_it_cannot_decode() {
+        let mut game = Game::classic();
+        game.outcome_symbols = "LDW".chars().collect();
+        let plays = parse_plays(&game, &lines("A X\nB W")).unwrap();
+        assert_eq!(
+            breakdown(&game, &plays, &AsGesture).err().unwrap(),
+            "line 2: 'W' does not name one of the gestures"
+        );
+        assert_eq!(
+            breakdown(&game, &plays, &AsOutcome).err().unwrap(),
+            "line 1: 'X' does not name one of the outcomes"
+        );
+    }
+
+    #[test]
+    fn histograms_count_outcomes_and_gestures() {
+        let game = Game::classic();
+        let plays = parse_plays(&game, &lines("A Y\nB X\nC Z\nA Y")).unwrap();
+        let rounds = breakdown(&game, &plays, &AsGesture).unwrap();
+        let rows = histograms(&game, &rounds);
+        let expected = [
+            ("outcome", "lose", 1),
+            ("outcome", "draw", 1),
+            ("outcome", "win", 2),
+            ("gesture", "Rock", 1),
+            ("gesture", "Paper", 2),
+            ("gesture", "Scissors", 1),
+        ]
+        .map(|(kind, name, count)| (kind, name.to_string(), count));
+        assert_eq!(rows, expected);
+    }
+
+    #[test]
+    fn csv_exports_escape_gesture_names() {
+        let mut game = Game::classic();
+        game.names[0] = "Rock, big".to_string();
+        game.names[1] = "\"Paper\"".to_string();
+        let plays = parse_plays(&game, &lines("B X")).unwrap();
+        let rounds = breakdown(&game, &plays, &AsGesture).unwrap();
+        assert_eq!(
+            rounds_csv(&game, &rounds),
+            "line,opponent,you,outcome,shape_score,outcome_score,score\n\
+             1,\"\"\"Paper\"\"\",\"Rock, big\",lose,1,0,1\n"
+        );
+        let rows = histograms(&game, &rounds);
+        assert!(histograms_csv(&rows).contains("gesture,\"Rock, big\",1\n"));
+    }
 }
@@ -0,0 +1,244 @@
+//! Search over the possible meanings of the guide's second column.
+//!
+//! Every injective assignment of the symbols used in the guide to gestures
+//! (or outcomes) is tried. The plays are first folded into a table of the
+//! score each symbol earns under each meaning, so the assignments are walked
+//! by backtracking, adding up one symbol at a time, without walking the
+//! guide again or storing the assignments.
+
+use std::fmt::Display;
+
+use crate::game::Game;
+use crate::game::Gesture;
+use crate::Play;
+
+#[derive(Debug, Clone)]
+pub struct Mapping<T> {
+    pub symbols: Vec<char>,
+    pub targets: Vec<T>,
+    pub score: i64,
+}
+
+impl<T> Mapping<T> {
+    pub fn map<U>(self, f: impl Fn(T) -> U) -> Mapping<U> {
+        Mapping {
+            symbols: self.symbols,
+            targets: self.targets.into_iter().map(f).collect(),
+            score: self.score,
+        }
+    }
+}
+
+impl<T: Display> Display for Mapping<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let pairs = self
+            .symbols
+            .iter()
+            .zip(&self.targets)
+            .map(|(symbol, target)| format!("{symbol}={target}"))
+            .collect::<Vec<_>>();
+        write!(f, "{} -> {}", pairs.join(" "), self.score)
+    }
+}
+
+/// Most choices `search` accepts. The walk tries up to `n!` assignments,
+/// about 40 million for 11 gestures, and grows elevenfold and more with
+/// every further gesture.
+pub const MAX_CHOICES: usize = 11;
+
+/// Best and worst scoring mapping; ties go to the mapping found first.
+pub struct Extremes<T> {
+    pub best: Mapping<T>,
+    pub worst: Mapping<T>,
+    pub tried: usize,
+}
+
+/// Depth-first walk over the injective assignments of symbols to targets,
+/// in lexicographic order, keeping the best and worst total score.
+struct Walk<'a> {
+    /// `gain[symbol][target]`: score of all the rounds playing `symbol` if it
+    /// means `targets[target]`.
+    gain: &'a [Vec<i64>],
+    used: Vec<bool>,
+    current: Vec<usize>,
+    best: Option<(i64, Vec<usize>)>,
+    worst: Option<(i64, Vec<usize>)>,
+    tried: usize,
+}
+
+impl Walk<'_> {
+    fn extend(&mut self, score: i64) {
+        let symbol = self.current.len();
+        if symbol == self.gain.len() {
+            self.tried += 1;
+            if self.best.as_ref().is_none_or(|(best, _)| score > *best) {
+                self.best = Some((score, self.current.clone()));
+            }
+            if self.worst.as_ref().is_none_or(|(worst, _)| score < *worst) {
+                self.worst = Some((score, self.current.clone()));
+            }
+            return;
+        }
+        for target in 0..self.used.len() {
+            if !self.used[target] {
+                self.used[target] = true;
+                self.current.push(target);
+                self.extend(score + self.gain[symbol][target]);
+                self.current.pop();
+                self.used[target] = false;
+            }
+        }
+    }
+}
+
+/// Tries every way of giving the guide's symbols distinct meanings from
+/// `targets`, scoring a round with `round(opponent, target)`. Fails if the
+/// guide is empty, uses more symbols than there are targets or if there are
+/// more than [`MAX_CHOICES`] targets.
+pub fn search<T: Copy>(
+    game: &Game,
+    plays: &[Play],
+    targets: &[T],
+    round: impl Fn(Gesture, T) -> i32,
+) -> Result<Extremes<T>, String> {
+    if targets.len() > MAX_CHOICES {
+        return Err(format!(
+            "{} choices are too many to search, at most {MAX_CHOICES} are supported",
+            targets.len()
+        ));
+    }
+    if plays.is_empty() {
+        return Err("the guide is empty".to_string());
+    }
+    let mut symbols = plays.iter().map(|x| x.you).collect::<Vec<_>>();
+    symbols.sort();
+    symbols.dedup();
+    if symbols.len() > targets.len() {
+        return Err(format!(
+            "the guide uses {} symbols but there are only {} choices",
+            symbols.len(),
+            targets.len()
+        ));
+    }
+    let n = game.names.len();
+    let mut counts = vec![vec![0i64; symbols.len()]; n];
+    for play in plays {
+        let symbol = symbols.binary_search(&play.you).unwrap();
+        counts[play.opponent.0][symbol] += 1;
+    }
+    let gain = (0..symbols.len())
+        .map(|symbol| {
+            targets
+                .iter()
+                .map(|&target| {
+                    (0..n)
+                        .map(|opponent| {
+                            counts[opponent][symbol] * round(Gesture(opponent), target) as i64
+                        })
+                        .sum()
+                })
+                .collect()
+        })
+        .collect::<Vec<Vec<i64>>>();
+    let mut walk = Walk {
+        gain: &gain,
+        used: vec![false; targets.len()],
+        current: Vec::with_capacity(symbols.len()),
+        best: None,
+        worst: None,
+        tried: 0,
+    };
+    walk.extend(0);
+    let mapping = |(score, arrangement): (i64, Vec<usize>)| Mapping {
+        symbols: symbols.clone(),
+        targets: arrangement.iter().map(|&x| targets[x]).collect(),
+        score,
+    };
+    Ok(Extremes {
+        best: mapping(walk.best.unwrap()),
+        worst: mapping(walk.worst.unwrap()),
+        tried: walk.tried,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Plays of `guide`, lines like `A Y`, in the classic game.
+    fn plays(guide: &[&str]) -> Vec<Play> {
+        let game = Game::classic();
+        guide
+            .iter()
+            .enumerate()
+            .map(|(idx, line)| {
+                let mut chars = line.chars();
+                Play {
+                    line: idx + 1,
+                    opponent: game.opponent(chars.next().unwrap()).unwrap(),
+                    you: chars.nth(1).unwrap(),
+                }
+            })
+            .collect()
+    }
+
+    fn by_gesture(plays: &[Play]) -> Result<Extremes<&'static str>, String> {
+        let game = Game::classic();
+        let gestures = game.gestures().collect::<Vec<_>>();
+        let names = ["Rock", "Paper", "Scissors"];
+        search(&game, plays, &gestures, |opponent, you| {
+            game.score(you, opponent)
+        })
+        .map(|x| Extremes {
+            best: x.best.map(|g| names[g.0]),
+            worst: x.worst.map(|g| names[g.0]),
+            tried: x.tried,
+        })
+    }
+
+    #[test]
+    fn best_and_worst_mapping_of_the_sample() {
+        let found = by_gesture(&plays(&["A Y", "B X", "C Z"])).unwrap();
+        assert_eq!(found.tried, 6);
+        assert_eq!(found.best.symbols, ['X', 'Y', 'Z']);
+        assert_eq!(found.best.targets, ["Scissors", "Paper", "Rock"]);
+        assert_eq!(found.best.score, 24);
+        assert_eq!(found.worst.targets, ["Rock", "Scissors", "Paper"]);
+        assert_eq!(found.worst.score, 6);
+        assert_eq!(found.best.to_string(), "X=Scissors Y=Paper Z=Rock -> 24");
+    }
+
+    #[test]
+    fn ties_go_to_the_first_mapping() {
+        let game = Game::classic();
+        let found = search(&game, &plays(&["A Z", "B X"]), &[1, 2, 3], |_, _| 5).unwrap();
+        assert_eq!(found.tried, 6);
+        assert_eq!(found.best.symbols, ['X', 'Z']);
+        assert_eq!((found.best.targets, found.best.score), (vec![1, 2], 10));
+        assert_eq!((found.worst.targets, found.worst.score), (vec![1, 2], 10));
+    }
+
+    #[test]
+    fn fewer_symbols_than_targets() {
+        let found = by_gesture(&plays(&["A Y", "C Y"])).unwrap();
+        assert_eq!(found.tried, 3);
+        assert_eq!(found.best.targets, ["Rock"]);
+        assert_eq!(found.best.score, 4 + 7);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(by_gesture(&[]).err().unwrap(), "the guide is empty");
+        let guide = plays(&["A X", "B Y", "C Z", "A W"]);
+        assert_eq!(
+            by_gesture(&guide).err().unwrap(),
+            "the guide uses 4 symbols but there are only 3 choices"
+        );
+        let targets = (0..=MAX_CHOICES).collect::<Vec<_>>();
+        let error = search(&Game::classic(), &guide, &targets, |_, _| 0).err();
+        assert_eq!(
+            error.unwrap(),
+            "12 choices are too many to search, at most 11 are supported"
+        );
+    }
+}
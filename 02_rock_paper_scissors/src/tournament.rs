@@ -0,0 +1,211 @@
+//! Round-robin tournaments between playing strategies.
+//!
+//! Every pair of strategies plays a match of a fixed number of rounds; the
+//! strategies are reset before each match and only see the moves of their
+//! current opponent. Randomness comes from one seeded generator, so a
+//! tournament is reproducible from its seed.
+
+use aoc::rng::Rng;
+
+use crate::game::Game;
+use crate::game::Gesture;
+use crate::game::Outcome;
+
+pub trait Strategy {
+    fn name(&self) -> String;
+
+    /// Forgets everything learned in the previous match.
+    fn reset(&mut self) {}
+
+    fn play(&mut self, game: &Game, rng: &mut Rng) -> Gesture;
+
+    /// Called after every round with the opponent's gesture.
+    fn observe(&mut self, _game: &Game, _opponent: Gesture) {}
+}
+
+/// Replays a fixed sequence of gestures, such as the decoded guide, from the
+/// start of every match.
+pub struct Fixed {
+    name: String,
+    moves: Vec<Gesture>,
+    next: usize,
+}
+
+impl Fixed {
+    pub fn new(name: &str, moves: Vec<Gesture>) -> Self {
+        assert!(!moves.is_empty(), "A fixed strategy needs moves");
+        Self {
+            name: name.to_string(),
+            moves,
+            next: 0,
+        }
+    }
+}
+
+impl Strategy for Fixed {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn reset(&mut self) {
+        self.next = 0;
+    }
+
+    fn play(&mut self, _game: &Game, _rng: &mut Rng) -> Gesture {
+        let gesture = self.moves[self.next % self.moves.len()];
+        self.next += 1;
+        gesture
+    }
+}
+
+pub struct Random;
+
+impl Strategy for Random {
+    fn name(&self) -> String {
+        "random".to_string()
+    }
+
+    fn play(&mut self, game: &Game, rng: &mut Rng) -> Gesture {
+        Gesture(rng.below(game.names.len()))
+    }
+}
+
+/// Beats the gesture the opponent has played most often so far.
+#[derive(Default)]
+pub struct FrequencyCounter {
+    counts: Vec<usize>,
+}
+
+impl Strategy for FrequencyCounter {
+    fn name(&self) -> String {
+        "frequency".to_string()
+    }
+
+    fn reset(&mut self) {
+        self.counts.clear();
+    }
+
+    fn play(&mut self, game: &Game, rng: &mut Rng) -> Gesture {
+        let Some(max) = self.counts.iter().max().filter(|&&x| x > 0) else {
+            return Gesture(rng.below(game.names.len()));
+        };
+        let favourites = (0..self.counts.len())
+            .filter(|&x| self.counts[x] == *max)
+            .collect::<Vec<_>>();
+        let favourite = Gesture(favourites[rng.below(favourites.len())]);
+        game.respond(favourite, Outcome::Win)
+    }
+
+    fn observe(&mut self, game: &Game, opponent: Gesture) {
+        self.counts.resize(game.names.len(), 0);
+        self.counts[opponent.0] += 1;
+    }
+}
+
+/// Plays whatever beats the opponent's previous gesture.
+#[derive(Default)]
+pub struct BeatLast {
+    last: Option<Gesture>,
+}
+
+impl Strategy for BeatLast {
+    fn name(&self) -> String {
+        "beat-last".to_string()
+    }
+
+    fn reset(&mut self) {
+        self.last = None;
+    }
+
+    fn play(&mut self, game: &Game, rng: &mut Rng) -> Gesture {
+        match self.last {
+            Some(last) => game.respond(last, Outcome::Win),
+            None => Gesture(rng.below(game.names.len())),
+        }
+    }
+
+    fn observe(&mut self, _game: &Game, opponent: Gesture) {
+        self.last = Some(opponent);
+    }
+}
+
+/// Walks through the gestures in cycle order.
+#[derive(Default)]
+pub struct Cycle {
+    next: usize,
+}
+
+impl Strategy for Cycle {
+    fn name(&self) -> String {
+        "cycle".to_string()
+    }
+
+    fn reset(&mut self) {
+        self.next = 0;
+    }
+
+    fn play(&mut self, game: &Game, _rng: &mut Rng) -> Gesture {
+        let gesture = Gesture(self.next % game.names.len());
+        self.next += 1;
+        gesture
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Standing {
+    pub name: String,
+    pub points: i64,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+}
+
+impl Standing {
+    pub fn rounds(&self) -> usize {
+        self.wins + self.draws + self.losses
+    }
+
+    fn record(&mut self, game: &Game, you: Gesture, opponent: Gesture) {
+        self.points += game.score(you, opponent) as i64;
+        match game.outcome(you, opponent) {
+            Outcome::Win => self.wins += 1,
+            Outcome::Draw => self.draws += 1,
+            Outcome::Lose => self.losses += 1,
+        }
+    }
+}
+
+/// Plays every pair of `strategies` against each other for `rounds` rounds
+/// and returns the standings sorted by points, best first.
+pub fn round_robin(
+    game: &Game,
+    strategies: &mut [Box<dyn Strategy>],
+    rounds: usize,
+    rng: &mut Rng,
+) -> Vec<Standing> {
+    let mut standings = strategies
+        .iter()
+        .map(|x| Standing {
+            name: x.name(),
+            ..Standing::default()
+        })
+        .collect::<Vec<_>>();
+    for a in 0..strategies.len() {
+        for b in a + 1..strategies.len() {
+            let (left, right) = strategies.split_at_mut(b);
+            let (first, second) = (&mut left[a], &mut right[0]);
+            first.reset();
+            second.reset();
+            for _ in 0..rounds {
+                let x = first.play(game, rng);
+                let y = second.play(game, rng);
+                first.observe(game, y);
+                second.observe(game, x);
+                standings[a].record(game, x, y);
+                standings[b].record(game, y, x);
+            }
+        }
+    }
+    standings.sort_by(|x, y| y.points.cmp(&x.points).then_with(|| x.name.cmp(&y.name)));
+    standings
+}
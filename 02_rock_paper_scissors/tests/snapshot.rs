@@ -0,0 +1,8 @@
+#[test]
+fn stdout_matches_snapshot() {
+    aoc::snapshot::assert_stdout(
+        env!("CARGO_BIN_EXE_rock_paper_scissors"),
+        "02_rock_paper_scissors",
+        cfg!(feature = "embed-inputs"),
+    );
+}
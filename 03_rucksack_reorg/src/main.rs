@@ -1,30 +1,244 @@
 use std::collections::HashSet;
-use std::env;
-use std::fs::File;
-use std::io::BufRead;
-use std::io::BufReader;
+use std::iter;
+use std::time::Duration;
+use std::time::Instant;
 
-fn score(val: char) -> i32 {
-    match val {
-        'a'..='z' => val as i32 - 96,
-        'A'..='Z' => val as i32 - 64 + 26,
-        _ => panic!("WTF"),
+use aoc::args::Args;
+use aoc::output::fail;
+use aoc::rng::Rng;
+use priorities::PriorityTable;
+
+mod priorities;
+
+#[cfg(feature = "embed-inputs")]
+const INPUT: Option<&str> = Some(include_str!("../../03_input.txt"));
+#[cfg(not(feature = "embed-inputs"))]
+const INPUT: Option<&str> = None;
+
+/// Column and character of the first character that is not an item.
+fn invalid_item(table: &PriorityTable, line: &str) -> Option<(usize, char)> {
+    line.chars()
+        .enumerate()
+        .find(|(_, x)| table.slot(*x).is_none())
+        .map(|(idx, x)| (idx + 1, x))
+}
+
+fn check_items(table: &PriorityTable, lines: &[String]) -> Result<(), String> {
+    for (idx, line) in lines.iter().enumerate() {
+        if let Some((column, x)) = invalid_item(table, line) {
+            return Err(format!(
+                "line {}, column {column}: invalid item {x:?}",
+                idx + 1
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn describe(table: &PriorityTable, items: ItemSet) -> String {
+    items.slots().map(|x| table.item(x)).collect()
+}
+
+/// Lists everything that breaks the puzzle's promise of exactly one item
+/// shared by the compartments of a rucksack and by the rucksacks of a group,
+/// and returns the number of problems found.
+fn validate(table: &PriorityTable, lines: &[String], compartments: usize, group: usize) -> usize {
+    let mut problems = Vec::new();
+    for (idx, line) in lines.iter().enumerate() {
+        if let Some((column, x)) = invalid_item(table, line) {
+            problems.push(format!(
+                "line {}, column {column}: invalid item {x:?}",
+                idx + 1
+            ));
+            continue;
+        }
+        match common_in_compartments(table, line, compartments) {
+            Err(e) => problems.push(format!("line {}: {e}", idx + 1)),
+            Ok(common) if common.len() != 1 => problems.push(format!(
+                "line {}: compartments share {} item types [{}]",
+                idx + 1,
+                common.len(),
+                describe(table, common)
+            )),
+            Ok(_) => {}
+        }
+    }
+    for (number, members) in lines.chunks(group).enumerate() {
+        let first = number * group + 1;
+        let last = first + members.len() - 1;
+        if members.len() < group {
+            problems.push(format!(
+                "lines {first}-{last}: incomplete group of {}",
+                members.len()
+            ));
+            continue;
+        }
+        if members.iter().any(|x| invalid_item(table, x).is_some()) {
+            continue;
+        }
+        let common = ItemSet::common(members.iter().map(|x| ItemSet::from_items(table, x)));
+        if common.len() != 1 {
+            problems.push(format!(
+                "lines {first}-{last}: no unique badge, {} common item types [{}]",
+                common.len(),
+                describe(table, common)
+            ));
+        }
+    }
+    println!(
+        "Validated {} rucksacks in groups of {group} with {compartments} compartments",
+        lines.len()
+    );
+    for problem in &problems {
+        println!("{problem}");
+    }
+    println!("Problems found: {}", problems.len());
+    problems.len()
+}
+
+/// Set of item types, bit `s` standing for the item in slot `s` of the
+/// priority table.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct ItemSet(u64);
+
+impl ItemSet {
+    const EMPTY: ItemSet = ItemSet(0);
+    const ALL: ItemSet = ItemSet(!0);
+
+    /// The set of one item already checked by `invalid_item`.
+    fn single(table: &PriorityTable, item: char) -> Self {
+        let slot = table
+            .slot(item)
+            .unwrap_or_else(|| panic!("Invalid item {item:?}"));
+        ItemSet(1 << slot)
+    }
+
+    fn from_items(table: &PriorityTable, items: &str) -> Self {
+        items.chars().fold(Self::EMPTY, |set, item| {
+            set.union(Self::single(table, item))
+        })
+    }
+
+    fn union(self, other: Self) -> Self {
+        ItemSet(self.0 | other.0)
+    }
+
+    fn intersection(self, other: Self) -> Self {
+        ItemSet(self.0 & other.0)
+    }
+
+    /// Items present in every one of `sets`.
+    fn common(sets: impl IntoIterator<Item = ItemSet>) -> Self {
+        sets.into_iter().fold(Self::ALL, Self::intersection)
+    }
+
+    fn len(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    /// Table slots of the items in the set, lowest first.
+    fn slots(self) -> impl Iterator<Item = u32> {
+        let mut bits = self.0;
+        iter::from_fn(move || {
+            if bits == 0 {
+                return None;
+            }
+            let slot = bits.trailing_zeros();
+            bits &= bits - 1;
+            Some(slot)
+        })
+    }
+
+    fn priority_sum(self, table: &PriorityTable) -> i32 {
+        self.slots().map(|x| table.slot_weight(x)).sum()
+    }
+}
+
+/// Items found in every one of the `k` equal compartments of a rucksack.
+fn common_in_compartments(table: &PriorityTable, line: &str, k: usize) -> Result<ItemSet, String> {
+    let count = line.chars().count();
+    if !count.is_multiple_of(k) {
+        return Err(format!(
+            "{count} items do not split into {k} equal compartments"
+        ));
+    }
+    let size = count / k;
+    if size == 0 {
+        return Ok(ItemSet::EMPTY);
+    }
+    let (mut common, mut compartment) = (ItemSet::ALL, ItemSet::EMPTY);
+    for (idx, item) in line.chars().enumerate() {
+        compartment = compartment.union(ItemSet::single(table, item));
+        if (idx + 1) % size == 0 {
+            common = common.intersection(compartment);
+            compartment = ItemSet::EMPTY;
+        }
+    }
+    Ok(common)
+}
+
+/// Items shared by all rucksacks of each consecutive group of `n`.
+fn common_in_groups(
+    table: &PriorityTable,
+    lines: &[String],
+    n: usize,
+) -> Result<Vec<ItemSet>, String> {
+    if !lines.len().is_multiple_of(n) {
+        return Err(format!(
+            "{} rucksacks do not form groups of {n}",
+            lines.len()
+        ));
+    }
+    Ok(lines
+        .chunks(n)
+        .map(|group| ItemSet::common(group.iter().map(|x| ItemSet::from_items(table, x))))
+        .collect())
+}
+
+fn pt1_sum(table: &PriorityTable, input: &[String], compartments: usize) -> Result<i32, String> {
+    input.iter().enumerate().try_fold(0i32, |sum, (idx, line)| {
+        let common = common_in_compartments(table, line, compartments)
+            .map_err(|e| format!("line {}: {e}", idx + 1))?;
+        Ok(sum + common.priority_sum(table))
+    })
+}
+
+fn pt2_sum(table: &PriorityTable, input: &[String], group: usize) -> Result<i32, String> {
+    Ok(common_in_groups(table, input, group)?
+        .into_iter()
+        .map(|x| x.priority_sum(table))
+        .sum())
+}
+
+fn pt1(table: &PriorityTable, input: &[String], compartments: usize) {
+    match pt1_sum(table, input, compartments) {
+        Ok(score) => println!("Solution of first part: {}", score),
+        Err(e) => fail(e),
     }
 }
 
-fn pt1(input: &Vec<String>) {
-    let score = input.iter().fold(0i32, |sum, line| {
+fn pt2(table: &PriorityTable, input: &[String], group: usize) {
+    match pt2_sum(table, input, group) {
+        Ok(score) => println!("Solution of second part: {}", score),
+        Err(e) => fail(e),
+    }
+}
+
+/// The former `HashSet<char>` based solutions, kept as the baseline for
+/// `--bench`.
+fn pt1_hashset(table: &PriorityTable, input: &[String]) -> i32 {
+    input.iter().fold(0i32, |sum, line| {
         let half_idx = line.len() / 2;
         let a = line.chars().take(half_idx).collect::<HashSet<char>>();
         let b = line.chars().skip(half_idx).collect::<HashSet<char>>();
         sum + a
             .intersection(&b)
-            .fold(0i32, |acc, item| acc + score(*item))
-    });
-    println!("Solution of first part: {}", score);
+            .fold(0i32, |acc, item| acc + table.weight(*item).unwrap())
+    })
 }
-fn pt2(input: &Vec<String>) {
-    let score = input
+
+fn pt2_hashset(table: &PriorityTable, input: &[String]) -> i32 {
+    input
         .iter()
         .step_by(3)
         .zip(input.iter().skip(1).step_by(3))
@@ -39,18 +253,191 @@ fn pt2(input: &Vec<String>) {
                 .cloned()
                 .collect::<HashSet<_>>()
                 .intersection(&third_set)
-                .fold(0i32, |acc_inner, item| acc_inner + score(*item))
-        });
-    println!("Solution of second part: {}", score);
+                .fold(0i32, |acc_inner, item| {
+                    acc_inner + table.weight(*item).unwrap()
+                })
+        })
+}
+
+/// `count` random rucksacks (a multiple of three) of 8 to 64 letters.
+fn generate(count: usize, seed: u64) -> Vec<String> {
+    const LETTERS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    let mut rng = Rng::new(seed);
+    (0..count.next_multiple_of(3))
+        .map(|_| {
+            let half = 4 + rng.below(29);
+            (0..2 * half)
+                .map(|_| LETTERS[rng.below(LETTERS.len())] as char)
+                .collect()
+        })
+        .collect()
+}
+
+fn time<T>(f: impl Fn() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+fn bench(count: usize, seed: u64) {
+    let table = PriorityTable::default();
+    let input = generate(count, seed);
+    let distinct = input
+        .iter()
+        .map(|x| ItemSet::from_items(&table, x).len() as usize)
+        .sum::<usize>();
+    println!(
+        "Benchmark: {} generated rucksacks, {:.1} item types each",
+        input.len(),
+        distinct as f64 / input.len() as f64
+    );
+    type Part = fn(&PriorityTable, &[String]) -> i32;
+    let parts: [(&str, Part, Part); 2] = [
+        ("pt1", pt1_hashset, |t, x| pt1_sum(t, x, 2).unwrap()),
+        ("pt2", pt2_hashset, |t, x| pt2_sum(t, x, 3).unwrap()),
+    ];
+    for (name, hashset, bitset) in parts {
+        let (expected, slow) = time(|| hashset(&table, &input));
+        let (actual, fast) = time(|| bitset(&table, &input));
+        assert_eq!(expected, actual, "{name} implementations disagree");
+        println!(
+            "{name}: hashset {slow:.2?}, bitset {fast:.2?}, speedup {:.1}x",
+            slow.as_secs_f64() / fast.as_secs_f64()
+        );
+    }
 }
 
 fn main() {
-    let filename = env::args().nth(1).expect("Missing filename argument");
-    let file = File::open(filename).expect("File not found");
-    let lines = BufReader::new(file)
-        .lines()
-        .flatten()
-        .collect::<Vec<String>>();
-    pt1(&lines);
-    pt2(&lines);
+    let _stats = aoc::stats::day();
+    let args = Args::from_env();
+    if let Some(count) = args.value("bench") {
+        let count = count.parse().unwrap_or_else(|_| {
+            fail(format!(
+                "--bench takes a number of rucksacks, not {count:?}"
+            ))
+        });
+        bench(count, args.parsed("seed", 2022u64));
+        return;
+    }
+    let lines = aoc::stats::phase("read", || aoc::input::lines_from(&args, INPUT));
+    let compartments = args.parsed("compartments", 2usize);
+    let group = args.parsed("group", 3usize);
+    if compartments == 0 || group == 0 {
+        fail("--compartments and --group must be at least 1".to_string());
+    }
+    let table = PriorityTable::load(args.value("priorities").unwrap_or("letters"))
+        .unwrap_or_else(|e| fail(e));
+    if args.flag("validate") {
+        let problems =
+            aoc::stats::phase("validate", || validate(&table, &lines, compartments, group));
+        aoc::stats::exit(if problems == 0 { 0 } else { 1 });
+    }
+    if let Err(e) = check_items(&table, &lines) {
+        fail(e);
+    }
+    aoc::stats::phase("pt1", || pt1(&table, &lines, compartments));
+    aoc::stats::phase("pt2", || pt2(&table, &lines, group));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(table: &PriorityTable, items: &str) -> ItemSet {
+        ItemSet::from_items(table, items)
+    }
+
+    #[test]
+    fn item_set_operations() {
+        let table = PriorityTable::default();
+        let (a, b) = (set(&table, "abcA"), set(&table, "cAZZ"));
+        assert_eq!(a.union(b), set(&table, "abcAZ"));
+        assert_eq!(a.intersection(b), set(&table, "cA"));
+        assert_eq!(ItemSet::common([a, b, set(&table, "A")]), set(&table, "A"));
+        assert_eq!(ItemSet::common([]), ItemSet::ALL);
+        assert_eq!(b.len(), 3);
+        assert_eq!(ItemSet::EMPTY.len(), 0);
+        assert_eq!(ItemSet::ALL.len(), 64);
+        assert_eq!(set(&table, "Zca").slots().collect::<Vec<_>>(), [0, 2, 51]);
+        assert_eq!(describe(&table, set(&table, "Zca")), "acZ");
+        assert_eq!(set(&table, "aaZ").priority_sum(&table), 1 + 52);
+        assert_eq!(ItemSet::EMPTY.priority_sum(&table), 0);
+    }
+
+    fn lines(items: &[&str]) -> Vec<String> {
+        items.iter().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn three_compartments() {
+        let table = PriorityTable::default();
+        let common = common_in_compartments(&table, "abXcdXXef", 3).unwrap();
+        assert_eq!(describe(&table, common), "X");
+        let common = common_in_compartments(&table, "abcabdabe", 3).unwrap();
+        assert_eq!(describe(&table, common), "ab");
+        assert_eq!(common_in_compartments(&table, "", 3), Ok(ItemSet::EMPTY));
+    }
+
+    #[test]
+    fn uneven_compartments_are_an_error() {
+        let table = PriorityTable::default();
+        assert_eq!(
+            common_in_compartments(&table, "abcde", 2),
+            Err("5 items do not split into 2 equal compartments".to_string())
+        );
+        assert_eq!(
+            pt1_sum(&table, &lines(&["aa", "abcabcab"]), 3),
+            Err("line 1: 2 items do not split into 3 equal compartments".to_string())
+        );
+    }
+
+    #[test]
+    fn groups_of_two() {
+        let table = PriorityTable::default();
+        let input = lines(&["abc", "cde", "Xy", "yXz"]);
+        let groups = common_in_groups(&table, &input, 2).unwrap();
+        let badges = groups
+            .iter()
+            .map(|x| describe(&table, *x))
+            .collect::<Vec<_>>();
+        assert_eq!(badges, ["c", "yX"]);
+        assert_eq!(pt2_sum(&table, &input, 2), Ok(3 + 25 + 50));
+    }
+
+    #[test]
+    fn incomplete_groups_are_an_error() {
+        let table = PriorityTable::default();
+        let input = lines(&["ab", "bc", "cd", "de"]);
+        assert_eq!(
+            common_in_groups(&table, &input, 3),
+            Err("4 rucksacks do not form groups of 3".to_string())
+        );
+        assert_eq!(
+            pt2_sum(&table, &input[..3], 2),
+            Err("3 rucksacks do not form groups of 2".to_string())
+        );
+    }
+
+    #[test]
+    fn bitsets_agree_with_hashsets() {
+        let table = PriorityTable::default();
+        let input = generate(90, 7);
+        for line in &input {
+            let (a, b) = line.split_at(line.len() / 2);
+            let a = a.chars().collect::<HashSet<_>>();
+            let b = b.chars().collect::<HashSet<_>>();
+            let mut expected = a.intersection(&b).copied().collect::<Vec<_>>();
+            expected.sort_by_key(|x| table.slot(*x));
+            let common = common_in_compartments(&table, line, 2).unwrap();
+            assert_eq!(describe(&table, common), String::from_iter(expected));
+        }
+        assert_eq!(
+            pt1_sum(&table, &input, 2).unwrap(),
+            pt1_hashset(&table, &input)
+        );
+        assert_eq!(
+            pt2_sum(&table, &input, 3).unwrap(),
+            pt2_hashset(&table, &input)
+        );
+    }
 }
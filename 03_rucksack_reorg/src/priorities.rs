@@ -0,0 +1,220 @@
+//! Tables assigning a slot and a weight to every known item type.
+//!
+//! A table is written one entry per line as `<items> [= <weight>]`. Items are
+//! single characters or `x-y` ranges, separated by whitespace, and get
+//! consecutive weights starting at `<weight>`, or right after the highest
+//! weight so far. Lines starting with `#` are comments. The puzzle's own
+//! priorities are:
+//!
+//! ```text
+//! a-z
+//! A-Z
+//! ```
+//!
+//! A table holds at most 64 item types so that item sets fit in a `u64`.
+
+use std::collections::HashMap;
+use std::fs;
+
+pub const MAX_ITEMS: usize = 64;
+
+const BUILTIN: [(&str, &str); 3] = [
+    ("letters", "a-z\nA-Z\n"),
+    ("digits", "0-9\n"),
+    ("alphanumeric", "a-z\nA-Z\n0-9\n"),
+];
+
+#[derive(Debug, Clone)]
+pub struct PriorityTable {
+    /// Slot of each ASCII item, `u8::MAX` if unknown.
+    ascii: [u8; 128],
+    other: HashMap<char, u8>,
+    items: Vec<char>,
+    weights: Vec<i32>,
+}
+
+impl Default for PriorityTable {
+    fn default() -> Self {
+        Self::parse(BUILTIN[0].1).unwrap()
+    }
+}
+
+impl PriorityTable {
+    fn empty() -> Self {
+        Self {
+            ascii: [u8::MAX; 128],
+            other: HashMap::new(),
+            items: Vec::new(),
+            weights: Vec::new(),
+        }
+    }
+
+    /// A built-in table by name, or else a table file.
+    pub fn load(spec: &str) -> Result<Self, String> {
+        if let Some((_, config)) = BUILTIN.iter().find(|(name, _)| *name == spec) {
+            return Self::parse(config);
+        }
+        let config = fs::read_to_string(spec).map_err(|e| format!("{spec}: {e}"))?;
+        Self::parse(&config).map_err(|e| format!("{spec}: {e}"))
+    }
+
+    pub fn parse(config: &str) -> Result<Self, String> {
+        let mut table = Self::empty();
+        for (idx, line) in config.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let err = |e: String| format!("line {}: {e}", idx + 1);
+            let (items, weight) = match line.rsplit_once('=') {
+                Some((items, weight)) => {
+                    let weight = weight
+                        .trim()
+                        .parse::<i32>()
+                        .map_err(|_| err(format!("invalid weight {:?}", weight.trim())))?;
+                    (items, Some(weight))
+                }
+                None => (line, None),
+            };
+            let mut next =
+                weight.unwrap_or_else(|| table.weights.iter().max().map_or(1, |x| x + 1));
+            for token in items.split_whitespace() {
+                let chars = token.chars().collect::<Vec<_>>();
+                let range = match chars[..] {
+                    [from, '-', to] if from <= to => from..=to,
+                    [from, '-', to] => return Err(err(format!("empty range {from}-{to}"))),
+                    _ => {
+                        for item in chars {
+                            table.insert(item, next).map_err(err)?;
+                            next += 1;
+                        }
+                        continue;
+                    }
+                };
+                for item in range {
+                    table.insert(item, next).map_err(err)?;
+                    next += 1;
+                }
+            }
+        }
+        if table.items.is_empty() {
+            return Err("the table has no items".to_string());
+        }
+        Ok(table)
+    }
+
+    fn insert(&mut self, item: char, weight: i32) -> Result<(), String> {
+        if self.slot(item).is_some() {
+            return Err(format!("item {item:?} listed twice"));
+        }
+        if self.items.len() == MAX_ITEMS {
+            return Err(format!("more than {MAX_ITEMS} item types"));
+        }
+        let slot = self.items.len() as u8;
+        match u8::try_from(item) {
+            Ok(byte) if byte < 128 => self.ascii[byte as usize] = slot,
+            _ => {
+                self.other.insert(item, slot);
+            }
+        }
+        self.items.push(item);
+        self.weights.push(weight);
+        Ok(())
+    }
+
+    pub fn slot(&self, item: char) -> Option<u32> {
+        let slot = match u8::try_from(item) {
+            Ok(byte) if byte < 128 => self.ascii[byte as usize],
+            _ => *self.other.get(&item)?,
+        };
+        (slot != u8::MAX).then_some(slot as u32)
+    }
+
+    pub fn weight(&self, item: char) -> Option<i32> {
+        self.slot(item).map(|x| self.weights[x as usize])
+    }
+
+    pub fn item(&self, slot: u32) -> char {
+        self.items[slot as usize]
+    }
+
+    pub fn slot_weight(&self, slot: u32) -> i32 {
+        self.weights[slot as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weights(table: &PriorityTable, items: &str) -> Vec<Option<i32>> {
+        items.chars().map(|x| table.weight(x)).collect()
+    }
+
+    fn error(config: &str) -> String {
+        PriorityTable::parse(config).unwrap_err()
+    }
+
+    #[test]
+    fn ranges_and_single_items() {
+        let table = PriorityTable::parse("a-c\nx yz\nq-q").unwrap();
+        assert_eq!(weights(&table, "abcxyzq"), [1, 2, 3, 4, 5, 6, 7].map(Some));
+        assert_eq!(table.weight('d'), None);
+        assert_eq!((table.slot('x'), table.item(3)), (Some(3), 'x'));
+        let letters = PriorityTable::default();
+        assert_eq!(
+            weights(&letters, "azAZ"),
+            [Some(1), Some(26), Some(27), Some(52)]
+        );
+    }
+
+    #[test]
+    fn weights_override_and_continue_from_the_highest() {
+        let table = PriorityTable::parse("a-c = 10\nz\nq = 5\nr s=-2\nt").unwrap();
+        assert_eq!(
+            weights(&table, "abczqrst"),
+            [10, 11, 12, 13, 5, -2, -1, 14].map(Some)
+        );
+        assert_eq!(table.slot_weight(table.slot('q').unwrap()), 5);
+        assert_eq!(error("a = x"), "line 1: invalid weight \"x\"");
+    }
+
+    #[test]
+    fn comments_and_blank_lines_are_skipped() {
+        let table = PriorityTable::parse("# weights\n\n   \n  a b  \n# c\n").unwrap();
+        assert_eq!(weights(&table, "ab#c"), [Some(1), Some(2), None, None]);
+        assert_eq!(error("# nothing\n\n"), "the table has no items");
+    }
+
+    #[test]
+    fn duplicate_items_are_an_error() {
+        assert_eq!(error("a-c\n\nb"), "line 3: item 'b' listed twice");
+        assert_eq!(error("xyx"), "line 1: item 'x' listed twice");
+    }
+
+    #[test]
+    fn reversed_ranges_are_an_error() {
+        assert_eq!(error("a\nz-a"), "line 2: empty range z-a");
+    }
+
+    #[test]
+    fn at_most_64_items() {
+        let full = "a-z\nA-Z\n0-9\n+ /\n";
+        assert!(PriorityTable::parse(full).is_ok());
+        assert_eq!(
+            error(&format!("{full}!")),
+            "line 5: more than 64 item types"
+        );
+        assert_eq!(error("\u{100}-\u{200}"), "line 1: more than 64 item types");
+    }
+
+    #[test]
+    fn non_ascii_items() {
+        let table = PriorityTable::parse("é ü\nα-γ = 100\n").unwrap();
+        assert_eq!(
+            weights(&table, "éüαβγe"),
+            [Some(1), Some(2), Some(100), Some(101), Some(102), None]
+        );
+        assert_eq!(table.item(table.slot('β').unwrap()), 'β');
+    }
+}
@@ -0,0 +1,8 @@
+#[test]
+fn stdout_matches_snapshot() {
+    aoc::snapshot::assert_stdout(
+        env!("CARGO_BIN_EXE_rucksack_reorg"),
+        "03_rucksack_reorg",
+        cfg!(feature = "embed-inputs"),
+    );
+}
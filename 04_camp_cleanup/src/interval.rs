@@ -0,0 +1,354 @@
+//! Inclusive integer ranges and normalized sets of them.
+//!
+//! `IntervalSet` keeps its ranges sorted, disjoint and non-adjacent, so two
+//! sets covering the same sections compare equal.
+
+use std::fmt;
+use std::mem;
+
+pub trait Integer: Copy + Ord + fmt::Debug + fmt::Display {
+    /// Number of steps from `self` up to `other`, which is not below it.
+    fn distance(self, other: Self) -> u128;
+
+    /// The next value, `None` at the top of the type.
+    fn succ(self) -> Option<Self>;
+
+    /// The previous value, `None` at the bottom of the type.
+    fn pred(self) -> Option<Self>;
+}
+
+macro_rules! integer {
+    ($($t:ty)*) => {
+        $(
+            impl Integer for $t {
+                fn distance(self, other: Self) -> u128 {
+                    other.abs_diff(self) as u128
+                }
+
+                fn succ(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+
+                fn pred(self) -> Option<Self> {
+                    self.checked_sub(1)
+                }
+            }
+        )*
+    };
+}
+
+integer!(i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize);
+
+/// The sections `from..=to`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Range<T> {
+    pub from: T,
+    pub to: T,
+}
+
+impl<T: Integer> Range<T> {
+    /// `None` if `from > to`.
+    pub fn new(from: T, to: T) -> Option<Self> {
+        (from <= to).then_some(Self { from, to })
+    }
+
+    /// Number of sections, counted in `u128` so that it never overflows
+    /// `T`; only a range spanning all of `i128` or `u128` saturates.
+    pub fn size(&self) -> u128 {
+        self.from.distance(self.to).saturating_add(1)
+    }
+
+    pub fn contains_section(&self, section: T) -> bool {
+        self.from <= section && section <= self.to
+    }
+
+    /// Whether every section of `other` is in `self`.
+    pub fn contains(&self, other: &Self) -> bool {
+        self.from <= other.from && self.to >= other.to
+    }
+
+    pub fn overlap(&self, other: &Self) -> bool {
+        self.from <= other.to && other.from <= self.to
+    }
+
+    /// Whether the ranges overlap or `other` starts right after `self` ends
+    /// (or the other way round).
+    pub fn touches(&self, other: &Self) -> bool {
+        let (first, second) = if self.from <= other.from {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        first.to.succ().is_none_or(|next| second.from <= next)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        Self::new(self.from.max(other.from), self.to.min(other.to))
+    }
+
+    /// The single range covering both, if they touch.
+    pub fn union(&self, other: &Self) -> Option<Self> {
+        self.touches(other).then(|| Self {
+            from: self.from.min(other.from),
+            to: self.to.max(other.to),
+        })
+    }
+
+    /// Sections of `self` not in `other`: up to one range on each side.
+    pub fn difference(&self, other: &Self) -> Vec<Self> {
+        if !self.overlap(other) {
+            return vec![*self];
+        }
+        let mut pieces = Vec::with_capacity(2);
+        if let Some(left) = other.from.pred().and_then(|to| Self::new(self.from, to)) {
+            pieces.push(left);
+        }
+        if let Some(right) = other.to.succ().and_then(|from| Self::new(from, self.to)) {
+            pieces.push(right);
+        }
+        pieces
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Range<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.from, self.to)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T: Integer> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self { ranges: Vec::new() }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for IntervalSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (idx, range) in self.ranges.iter().enumerate() {
+            if idx > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{range}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Integer> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut ranges = iter.into_iter().collect::<Vec<_>>();
+        ranges.sort();
+        Self {
+            ranges: merge_sorted(ranges),
+        }
+    }
+}
+
+/// Merges touching ranges of a list sorted by start.
+fn merge_sorted<T: Integer>(ranges: Vec<Range<T>>) -> Vec<Range<T>> {
+    let mut merged: Vec<Range<T>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged
+            .last_mut()
+            .and_then(|last| last.union(&range).map(|x| (last, x)))
+        {
+            Some((last, union)) => *last = union,
+            None => merged.push(range),
+        }
+    }
+    merged
+}
+
+impl<T: Integer> IntervalSet<T> {
+    /// The disjoint, sorted ranges of the set.
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Total number of sections in the set, saturating like `Range::size`.
+    pub fn len(&self) -> u128 {
+        self.ranges
+            .iter()
+            .map(Range::size)
+            .fold(0, u128::saturating_add)
+    }
+
+    pub fn insert(&mut self, range: Range<T>) {
+        let at = self.ranges.partition_point(|x| x < &range);
+        self.ranges.insert(at, range);
+        self.ranges = merge_sorted(mem::take(&mut self.ranges));
+    }
+
+    /// The range of the set holding `section`.
+    fn find(&self, section: T) -> Option<&Range<T>> {
+        let idx = self.ranges.partition_point(|x| x.to < section);
+        self.ranges.get(idx).filter(|x| x.contains_section(section))
+    }
+
+    pub fn contains_section(&self, section: T) -> bool {
+        self.find(section).is_some()
+    }
+
+    /// Whether every section of `range` is in the set.
+    pub fn contains_range(&self, range: &Range<T>) -> bool {
+        self.find(range.from).is_some_and(|x| x.contains(range))
+    }
+
+    /// Whether every section of `other` is in `self`.
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.ranges.iter().all(|x| self.contains_range(x))
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        other.is_superset(self)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.ranges.iter().chain(&other.ranges).copied().collect()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (mut a, mut b) = (0, 0);
+        let mut ranges = Vec::new();
+        while a < self.ranges.len() && b < other.ranges.len() {
+            let (x, y) = (&self.ranges[a], &other.ranges[b]);
+            if let Some(common) = x.intersection(y) {
+                ranges.push(common);
+            }
+            if x.to < y.to {
+                a += 1;
+            } else {
+                b += 1;
+            }
+        }
+        Self { ranges }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let mut b = 0;
+        for range in &self.ranges {
+            let mut rest = Some(*range);
+            while let Some(current) = rest {
+                while b < other.ranges.len() && other.ranges[b].to < current.from {
+                    b += 1;
+                }
+                match other.ranges.get(b).filter(|x| x.overlap(&current)) {
+                    None => {
+                        ranges.push(current);
+                        rest = None;
+                    }
+                    Some(cut) => {
+                        rest = None;
+                        for piece in current.difference(cut) {
+                            if piece.to < cut.from {
+                                ranges.push(piece);
+                            } else {
+                                rest = Some(piece);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Self { ranges }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range<T: Integer>(from: T, to: T) -> Range<T> {
+        Range::new(from, to).unwrap()
+    }
+
+    fn set<T: Integer>(ranges: &[(T, T)]) -> IntervalSet<T> {
+        ranges.iter().map(|&(from, to)| range(from, to)).collect()
+    }
+
+    #[test]
+    fn reversed_range_is_rejected() {
+        assert_eq!(Range::new(3, 2), None);
+    }
+
+    #[test]
+    fn succ_and_pred_stop_at_the_bounds() {
+        assert_eq!(i8::MAX.succ(), None);
+        assert_eq!(i8::MIN.pred(), None);
+        assert_eq!(u8::MIN.pred(), None);
+        assert_eq!(126i8.succ(), Some(127));
+    }
+
+    #[test]
+    fn size_does_not_overflow() {
+        assert_eq!(range(-2_000_000_000, 2_000_000_000).size(), 4_000_000_001);
+        assert_eq!(range(i8::MIN, i8::MAX).size(), 256);
+        assert_eq!(range(u128::MIN, u128::MAX).size(), u128::MAX);
+        assert_eq!(
+            set(&[(i32::MIN, -1), (1, i32::MAX)]).len(),
+            u32::MAX as u128
+        );
+    }
+
+    #[test]
+    fn touches_at_the_type_bounds() {
+        assert!(range(0i8, 126).touches(&range(127, 127)));
+        assert!(range(127i8, 127).touches(&range(0, 126)));
+        assert!(!range(0i8, 125).touches(&range(127, 127)));
+        assert_eq!(
+            range(i8::MIN, -1).union(&range(0, i8::MAX)),
+            Some(range(i8::MIN, i8::MAX))
+        );
+    }
+
+    #[test]
+    fn adjacent_ranges_merge() {
+        assert_eq!(set(&[(4, 6), (1, 3)]).ranges(), &[range(1, 6)]);
+        assert_eq!(set(&[(1, 3), (5, 6)]).ranges(), &[range(1, 3), range(5, 6)]);
+        assert_eq!(set(&[(1, 3), (5, 6), (4, 4)]), set(&[(1, 6)]));
+        let mut inserted = set(&[(1, 3), (7, 9)]);
+        inserted.insert(range(4, 6));
+        assert_eq!(inserted, set(&[(1, 9)]));
+    }
+
+    #[test]
+    fn range_difference_at_the_type_bounds() {
+        let all = range(i8::MIN, i8::MAX);
+        assert_eq!(all.difference(&range(i8::MIN, 0)), vec![range(1, i8::MAX)]);
+        assert_eq!(all.difference(&range(0, i8::MAX)), vec![range(i8::MIN, -1)]);
+        assert_eq!(all.difference(&all), vec![]);
+    }
+
+    #[test]
+    fn set_difference_across_several_cuts() {
+        let cuts = set(&[(-3, -1), (2, 3), (5, 5), (10, 12), (19, 25)]);
+        assert_eq!(
+            set(&[(0, 20), (30, 31)]).difference(&cuts),
+            set(&[(0, 1), (4, 4), (6, 9), (13, 18), (30, 31)])
+        );
+        assert!(set(&[(2, 3)]).difference(&cuts).is_empty());
+    }
+
+    #[test]
+    fn intersection_and_superset() {
+        let a = set(&[(1, 5), (8, 12)]);
+        let b = set(&[(4, 9), (12, 20)]);
+        assert_eq!(a.intersection(&b), set(&[(4, 5), (8, 9), (12, 12)]));
+        assert_eq!(a.union(&b), set(&[(1, 20)]));
+        assert!(a.union(&b).is_superset(&a));
+        assert!(a.is_subset(&a.union(&b)));
+        assert!(!a.is_superset(&b));
+        assert!(!a.contains_range(&range(5, 8)));
+        assert!(a.contains_section(12));
+    }
+}
@@ -0,0 +1,5 @@
+//! Interval toolkit used by the camp cleanup puzzle.
+
+pub mod interval;
+pub mod parse;
+pub mod sweep;
@@ -1,80 +1,142 @@
-use std::env;
-use std::fs::File;
-use std::io::BufRead;
-use std::io::BufReader;
+use std::fmt;
 
-struct Range {
-    from: i32,
-    to: i32,
-}
+use aoc::args::Args;
+use aoc::output::fail;
+use camp_cleanup::interval::IntervalSet;
+use camp_cleanup::interval::Range;
+use camp_cleanup::parse;
+use camp_cleanup::parse::Team;
+use camp_cleanup::sweep;
 
-impl Range {
-    fn contains(&self, other: &Self) -> bool {
-        self.from <= other.from && self.to >= other.to
-    }
-    fn overlap(&self, other: &Self) -> bool {
-        (self.from >= other.from && self.from <= other.to)
-            || (self.to >= other.from && self.to <= other.to)
-            || (other.from >= self.from && other.from <= self.to)
-            || (other.to >= self.from && other.to <= self.to)
-    }
-}
+#[cfg(feature = "embed-inputs")]
+const INPUT: Option<&str> = Some(include_str!("../../04_input.txt"));
+#[cfg(not(feature = "embed-inputs"))]
+const INPUT: Option<&str> = None;
 
-fn pt1(input: &Vec<String>) {
-    let count = input
+/// Teams where one elf's range contains the ranges of all the others.
+fn pt1(teams: &[Team<i32>]) {
+    let count = teams
         .iter()
-        .filter(|x| {
-            let values = x
-                .split(",")
-                .flat_map(|s| s.split("-"))
-                .map(|x| x.parse::<i32>())
-                .flatten()
-                .collect::<Vec<i32>>();
-            let r1 = Range {
-                from: values[0],
-                to: values[1],
-            };
-            let r2 = Range {
-                from: values[2],
-                to: values[3],
-            };
-            r1.contains(&r2) || r2.contains(&r1)
+        .filter(|team| {
+            team.ranges
+                .iter()
+                .any(|x| team.ranges.iter().all(|y| x.contains(y)))
         })
         .count();
     println!("First solution: {}", count);
 }
 
-fn pt2(input: &Vec<String>) {
-    let count = input
+/// Teams where the ranges of at least two elves overlap.
+fn pt2(teams: &[Team<i32>]) {
+    let count = teams
         .iter()
-        .filter(|x| {
-            let values = x
-                .split(",")
-                .flat_map(|s| s.split("-"))
-                .map(|x| x.parse::<i32>())
-                .flatten()
-                .collect::<Vec<i32>>();
-            let r1 = Range {
-                from: values[0],
-                to: values[1],
-            };
-            let r2 = Range {
-                from: values[2],
-                to: values[3],
-            };
-            r1.overlap(&r2)
+        .filter(|team| {
+            team.ranges
+                .iter()
+                .enumerate()
+                .any(|(idx, x)| team.ranges[idx + 1..].iter().any(|y| x.overlap(y)))
         })
         .count();
     println!("Second solution: {}", count);
 }
 
+/// Number of sections assigned to at least one elf.
+fn coverage(elves: &[Assignment]) {
+    let covered = elves.iter().map(|x| x.range).collect::<IntervalSet<_>>();
+    println!("Sections covered: {}", covered.len());
+}
+
+/// An elf's assignment: line of the input and position on that line.
+struct Assignment {
+    line: usize,
+    elf: usize,
+    range: Range<i32>,
+}
+
+impl fmt::Display for Assignment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {} elf {} ({})", self.line, self.elf, self.range)
+    }
+}
+
+fn assignments(teams: &[Team<i32>]) -> Vec<Assignment> {
+    teams
+        .iter()
+        .flat_map(|team| {
+            team.ranges
+                .iter()
+                .enumerate()
+                .map(|(idx, range)| Assignment {
+                    line: team.line,
+                    elf: idx + 1,
+                    range: *range,
+                })
+        })
+        .collect()
+}
+
+fn sweep(elves: &[Assignment], list_pairs: bool) {
+    let ranges = elves.iter().map(|x| x.range).collect::<Vec<_>>();
+    println!("Sweep over {} elves", elves.len());
+    let pairs = sweep::overlapping_pairs(&ranges);
+    println!("Overlapping pairs: {}", pairs.len());
+    if list_pairs {
+        for (a, b) in &pairs {
+            println!("  {} overlaps {}", elves[*a], elves[*b]);
+        }
+    }
+    if let Some(coverage) = sweep::max_coverage(&ranges) {
+        println!(
+            "Max coverage: {} elves on sections {}",
+            coverage.count, coverage.sections
+        );
+    }
+    let redundant = sweep::redundant(&ranges);
+    println!("Redundant elves: {}", redundant.len());
+    for (elf, container) in redundant {
+        println!("  {} inside {}", elves[elf], elves[container]);
+    }
+}
+
+fn report(elves: &[Assignment], over: usize) {
+    let ranges = elves.iter().map(|x| x.range).collect::<Vec<_>>();
+    let covered = ranges.iter().copied().collect::<IntervalSet<_>>();
+    let (Some(first), Some(last)) = (covered.ranges().first(), covered.ranges().last()) else {
+        println!("No assignments");
+        return;
+    };
+    let span = Range::new(first.from, last.to).unwrap();
+    println!("Coverage of {} elves over sections {span}", elves.len());
+    let gaps = IntervalSet::from_iter([span]).difference(&covered);
+    println!("Uncovered sections: {} [{gaps}]", gaps.len());
+    let crowded = sweep::covered_more_than(&ranges, over);
+    println!(
+        "Sections covered more than {over} times: {} [{crowded}]",
+        crowded.len()
+    );
+    let cover = sweep::minimal_cover(&ranges);
+    println!("Minimal cover: {} elves", cover.len());
+    for elf in cover {
+        println!("  {}", elves[elf]);
+    }
+}
+
 fn main() {
-    let filename = env::args().nth(1).expect("Missing filename argument");
-    let file = File::open(filename).expect("File not found");
-    let lines = BufReader::new(file)
-        .lines()
-        .flatten()
-        .collect::<Vec<String>>();
-    pt1(&lines);
-    pt2(&lines);
+    let _stats = aoc::stats::day();
+    let args = Args::from_env();
+    let team = args.parsed("team", 2usize);
+    let lines = aoc::stats::phase("read", || aoc::input::lines_from(&args, INPUT));
+    let teams =
+        aoc::stats::phase("parse", || parse::parse_teams(&lines, team)).unwrap_or_else(|e| fail(e));
+    let elves = assignments(&teams);
+    aoc::stats::phase("pt1", || pt1(&teams));
+    aoc::stats::phase("pt2", || pt2(&teams));
+    aoc::stats::phase("coverage", || coverage(&elves));
+    if args.flag("sweep") {
+        aoc::stats::phase("sweep", || sweep(&elves, args.flag("pairs")));
+    }
+    if args.flag("report") {
+        let over = args.parsed("over", 1usize);
+        aoc::stats::phase("report", || report(&elves, over));
+    }
 }
@@ -0,0 +1,116 @@
+//! Parsing of the section assignments, one team of elves per line.
+//!
+//! A line lists the ranges of the team separated by `,`, each written as
+//! `from-to` with `from <= to`. Negative bounds are allowed, as in `-5--3`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::interval::Integer;
+use crate::interval::Range;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+fn bound<T: FromStr>(text: &str, range: &str) -> Result<T, String> {
+    text.trim()
+        .parse()
+        .map_err(|_| format!("invalid section {:?} in {range:?}", text.trim()))
+}
+
+/// Parses `from-to`.
+pub fn parse_range<T: Integer + FromStr>(text: &str) -> Result<Range<T>, String> {
+    let trimmed = text.trim();
+    // the separator is the first `-` that cannot be the sign of `from`
+    let split = trimmed
+        .char_indices()
+        .skip(1)
+        .find(|(_, x)| *x == '-')
+        .map(|(idx, _)| idx);
+    let Some(split) = split else {
+        return Err(format!("expected from-to, got {trimmed:?}"));
+    };
+    let from = bound(&trimmed[..split], trimmed)?;
+    let to = bound(&trimmed[split + 1..], trimmed)?;
+    Range::new(from, to).ok_or_else(|| format!("range {trimmed:?} ends before it starts"))
+}
+
+/// Parses a line holding exactly `n` ranges.
+pub fn parse_team<T: Integer + FromStr>(line: &str, n: usize) -> Result<Vec<Range<T>>, String> {
+    let parts = line.split(',').collect::<Vec<_>>();
+    if parts.len() != n {
+        return Err(format!(
+            "expected {n} ranges, found {} in {line:?}",
+            parts.len()
+        ));
+    }
+    parts.into_iter().map(parse_range).collect()
+}
+
+/// The ranges of one line of the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Team<T> {
+    pub line: usize,
+    pub ranges: Vec<Range<T>>,
+}
+
+/// Parses every non-empty line as a team of `n` elves.
+pub fn parse_teams<T: Integer + FromStr>(
+    lines: &[String],
+    n: usize,
+) -> Result<Vec<Team<T>>, ParseError> {
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, x)| !x.trim().is_empty())
+        .map(|(idx, x)| {
+            let line = idx + 1;
+            match parse_team(x, n) {
+                Ok(ranges) => Ok(Team { line, ranges }),
+                Err(message) => Err(ParseError { line, message }),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn teams_keep_their_line_numbers() {
+        let teams = parse_teams::<i32>(&lines("1-2,3-4,5-6\n\n-5--3,-4-0,7-7\n"), 3).unwrap();
+        assert_eq!(teams.len(), 2);
+        assert_eq!(teams[1].line, 3);
+        assert_eq!(teams[1].ranges[0], Range::new(-5, -3).unwrap());
+        assert_eq!(teams[1].ranges[1], Range::new(-4, 0).unwrap());
+    }
+
+    #[test]
+    fn errors_name_the_line() {
+        let error = |text: &str| parse_teams::<i32>(&lines(text), 2).unwrap_err().to_string();
+        assert_eq!(
+            error("1-2,3-4\n5-3,1-2"),
+            "line 2: range \"5-3\" ends before it starts"
+        );
+        assert_eq!(
+            error("1-2,3-4,5-6"),
+            "line 1: expected 2 ranges, found 3 in \"1-2,3-4,5-6\""
+        );
+        assert_eq!(error("1-2,3-x"), "line 1: invalid section \"x\" in \"3-x\"");
+        assert_eq!(error("1-2,34"), "line 1: expected from-to, got \"34\"");
+    }
+}
@@ -0,0 +1,238 @@
+//! Sweep-line questions over many ranges at once.
+//!
+//! Every function sorts the ranges (or their endpoints) once and walks them
+//! in order, so all run in O(n log n), plus the size of the answer for
+//! `overlapping_pairs`. Ranges are referred to by their index in the input.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::interval::Integer;
+use crate::interval::IntervalSet;
+use crate::interval::Range;
+
+/// Order of the ranges by start, longer ranges first on equal starts.
+fn by_start<T: Integer>(ranges: &[Range<T>]) -> Vec<usize> {
+    let mut order = (0..ranges.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&x| (ranges[x].from, Reverse(ranges[x].to), x));
+    order
+}
+
+/// Every pair `(a, b)` with `a < b` of ranges sharing at least one section.
+pub fn overlapping_pairs<T: Integer>(ranges: &[Range<T>]) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+    // ranges started so far that may still overlap later ones, by end
+    let mut active: BinaryHeap<Reverse<(T, usize)>> = BinaryHeap::new();
+    let mut open = Vec::new();
+    for idx in by_start(ranges) {
+        let range = &ranges[idx];
+        while active
+            .peek()
+            .is_some_and(|Reverse((to, _))| *to < range.from)
+        {
+            active.pop();
+        }
+        open.clear();
+        open.extend(active.iter().map(|Reverse((_, other))| *other));
+        open.sort();
+        pairs.extend(open.iter().map(|&other| (other.min(idx), other.max(idx))));
+        active.push(Reverse((range.to, idx)));
+    }
+    pairs.sort();
+    pairs
+}
+
+/// Most ranges covering a single section, and the first stretch of sections
+/// covered that many times.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Coverage<T> {
+    pub count: usize,
+    pub sections: Range<T>,
+}
+
+pub fn max_coverage<T: Integer>(ranges: &[Range<T>]) -> Option<Coverage<T>> {
+    // starts sort before ends at the same position as ranges are inclusive
+    let mut events = ranges
+        .iter()
+        .flat_map(|x| [(x.from, false), (x.to, true)])
+        .collect::<Vec<_>>();
+    events.sort();
+    let mut depth = 0;
+    let mut best: Option<Coverage<T>> = None;
+    let mut pending = false;
+    for (idx, &(position, is_end)) in events.iter().enumerate() {
+        if let Some(best) = best.as_mut().filter(|_| pending) {
+            best.sections.to = if is_end {
+                position
+            } else {
+                position.pred().unwrap()
+            };
+            pending = false;
+        }
+        if is_end {
+            depth -= 1;
+            continue;
+        }
+        depth += 1;
+        let last_start_here = events
+            .get(idx + 1)
+            .is_none_or(|&(next, next_is_end)| next_is_end || next != position);
+        if last_start_here && best.is_none_or(|x| depth > x.count) {
+            best = Some(Coverage {
+                count: depth,
+                sections: Range {
+                    from: position,
+                    to: position,
+                },
+            });
+            pending = true;
+        }
+    }
+    best
+}
+
+/// Pairs `(redundant, container)` of ranges lying entirely inside another
+/// range. Of several identical ranges the first one is kept as container.
+pub fn redundant<T: Integer>(ranges: &[Range<T>]) -> Vec<(usize, usize)> {
+    let mut found = Vec::new();
+    // the range reaching furthest among those starting no later
+    let mut widest: Option<usize> = None;
+    for idx in by_start(ranges) {
+        match widest {
+            Some(container) if ranges[container].to >= ranges[idx].to => {
+                found.push((idx, container))
+            }
+            _ => widest = Some(idx),
+        }
+    }
+    found.sort();
+    found
+}
+
+/// Sections covered by more than `n` of the ranges.
+pub fn covered_more_than<T: Integer>(ranges: &[Range<T>], n: usize) -> IntervalSet<T> {
+    let mut events = ranges
+        .iter()
+        .flat_map(|x| [(x.from, false), (x.to, true)])
+        .collect::<Vec<_>>();
+    events.sort();
+    let mut depth = 0;
+    let mut opened = None;
+    let mut found = Vec::new();
+    for (position, is_end) in events {
+        if is_end {
+            if depth == n + 1 {
+                let from = opened.take().unwrap();
+                found.push(Range { from, to: position });
+            }
+            depth -= 1;
+        } else {
+            depth += 1;
+            if depth == n + 1 {
+                opened = Some(position);
+            }
+        }
+    }
+    found.into_iter().collect()
+}
+
+/// A smallest set of ranges covering the same sections as all of them,
+/// picked greedily: the range reaching furthest past the first uncovered
+/// section is always part of an optimal cover.
+pub fn minimal_cover<T: Integer>(ranges: &[Range<T>]) -> Vec<usize> {
+    let order = by_start(ranges);
+    let mut cover = Vec::new();
+    // last section covered by the ranges picked so far
+    let mut covered: Option<T> = None;
+    let mut i = 0;
+    while i < order.len() {
+        let range = &ranges[order[i]];
+        let target = match covered {
+            Some(last) if range.to <= last => {
+                i += 1;
+                continue;
+            }
+            Some(last) => match last.succ() {
+                Some(next) => next.max(range.from),
+                None => break,
+            },
+            None => range.from,
+        };
+        let mut best = order[i];
+        while i < order.len() && ranges[order[i]].from <= target {
+            if ranges[order[i]].to > ranges[best].to {
+                best = order[i];
+            }
+            i += 1;
+        }
+        cover.push(best);
+        covered = Some(ranges[best].to);
+    }
+    cover.sort();
+    cover
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranges(ranges: &[(i32, i32)]) -> Vec<Range<i32>> {
+        ranges
+            .iter()
+            .map(|&(from, to)| Range::new(from, to).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn overlapping_pairs_share_a_section() {
+        let input = ranges(&[(1, 3), (2, 4), (5, 6), (3, 3)]);
+        assert_eq!(overlapping_pairs(&input), vec![(0, 1), (0, 3), (1, 3)]);
+    }
+
+    #[test]
+    fn max_coverage_keeps_the_first_tie() {
+        assert_eq!(max_coverage::<i32>(&[]), None);
+        let separate = ranges(&[(5, 6), (1, 2)]);
+        assert_eq!(
+            max_coverage(&separate),
+            Some(Coverage {
+                count: 1,
+                sections: Range::new(1, 2).unwrap(),
+            })
+        );
+        let nested = ranges(&[(1, 5), (2, 3), (4, 6)]);
+        assert_eq!(
+            max_coverage(&nested),
+            Some(Coverage {
+                count: 2,
+                sections: Range::new(2, 3).unwrap(),
+            })
+        );
+    }
+
+    #[test]
+    fn redundant_ranges_point_at_the_first_container() {
+        let input = ranges(&[(1, 10), (2, 3), (1, 10), (11, 12)]);
+        assert_eq!(redundant(&input), vec![(1, 0), (2, 0)]);
+    }
+
+    #[test]
+    fn covered_more_than_counts_overlaps() {
+        let input = ranges(&[(1, 5), (3, 8), (4, 4)]);
+        assert_eq!(covered_more_than(&input, 1).to_string(), "3-5");
+        assert_eq!(covered_more_than(&input, 2).to_string(), "4-4");
+        assert!(covered_more_than(&input, 3).is_empty());
+    }
+
+    #[test]
+    fn minimal_cover_picks_the_furthest_reach() {
+        assert!(minimal_cover::<i32>(&[]).is_empty());
+        let chain = ranges(&[(1, 4), (2, 6), (3, 9), (5, 7), (10, 12)]);
+        assert_eq!(minimal_cover(&chain), vec![0, 2, 4]);
+        // starting with the first range would need three
+        let greedy = ranges(&[(1, 3), (1, 5), (4, 8), (6, 10)]);
+        assert_eq!(minimal_cover(&greedy), vec![1, 3]);
+        let gap = ranges(&[(1, 2), (5, 6), (1, 1)]);
+        assert_eq!(minimal_cover(&gap), vec![0, 1]);
+    }
+}
@@ -0,0 +1,8 @@
+#[test]
+fn stdout_matches_snapshot() {
+    aoc::snapshot::assert_stdout(
+        env!("CARGO_BIN_EXE_camp_cleanup"),
+        "04_camp_cleanup",
+        cfg!(feature = "embed-inputs"),
+    );
+}
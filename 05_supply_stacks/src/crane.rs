@@ -0,0 +1,158 @@
+//! Crane models for rearranging the stacks.
+//!
+//! Every move takes the top crates off the source stack in one go; the
+//! crane then decides in which order they land on the target stack.
+
+/// The label of a crate.
+pub type Crate = String;
+
+/// The crates of a stack, bottom first.
+pub type Stack = Vec<Crate>;
+
+pub trait Crane {
+    fn name(&self) -> String;
+
+    /// Puts `lifted`, the crates taken off the top of a stack (bottom
+    /// first), onto `to`.
+    fn place(&self, lifted: Stack, to: &mut Stack);
+}
+
+/// Moves one crate at a time, reversing the lifted crates.
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn name(&self) -> String {
+        "CrateMover 9000".to_string()
+    }
+
+    fn place(&self, lifted: Stack, to: &mut Stack) {
+        to.extend(lifted.into_iter().rev());
+    }
+}
+
+/// Moves all the crates at once, keeping their order.
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn name(&self) -> String {
+        "CrateMover 9001".to_string()
+    }
+
+    fn place(&self, lifted: Stack, to: &mut Stack) {
+        to.extend(lifted);
+    }
+}
+
+/// Moves at most `capacity` crates per lift, each lift keeping its order.
+/// A capacity of 1 is the CrateMover 9000.
+pub struct Limited {
+    pub capacity: usize,
+}
+
+impl Crane for Limited {
+    fn name(&self) -> String {
+        format!("limited crane ({} per lift)", self.capacity)
+    }
+
+    fn place(&self, lifted: Stack, to: &mut Stack) {
+        for lift in lifted.rchunks(self.capacity) {
+            to.extend_from_slice(lift);
+        }
+    }
+}
+
+/// Moves all the crates at once, turning the top `turns` crates of the
+/// lifted stack to its bottom on the way.
+pub struct Rotating {
+    pub turns: usize,
+}
+
+impl Crane for Rotating {
+    fn name(&self) -> String {
+        let plural = if self.turns == 1 { "" } else { "s" };
+        format!("rotating crane ({} turn{plural})", self.turns)
+    }
+
+    fn place(&self, mut lifted: Stack, to: &mut Stack) {
+        if !lifted.is_empty() {
+            let turns = self.turns % lifted.len();
+            lifted.rotate_right(turns);
+        }
+        to.extend(lifted);
+    }
+}
+
+/// A crane by name: `9000`, `9001`, `limited` or `rotating`.
+pub fn by_name(name: &str, capacity: usize, turns: usize) -> Result<Box<dyn Crane>, String> {
+    match name {
+        "9000" => Ok(Box::new(CrateMover9000)),
+        "9001" => Ok(Box::new(CrateMover9001)),
+        "limited" if capacity == 0 => Err("--capacity must be at least 1".to_string()),
+        "limited" => Ok(Box::new(Limited { capacity })),
+        "rotating" => Ok(Box::new(Rotating { turns })),
+        _ => Err(format!(
+            "unknown crane {name:?}, expected 9000, 9001, limited or rotating"
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stack(labels: &str) -> Stack {
+        labels.chars().map(String::from).collect()
+    }
+
+    /// `to` after `crane` places `lifted` on it.
+    fn place(crane: &dyn Crane, lifted: &str, to: &str) -> Stack {
+        let mut to = stack(to);
+        crane.place(stack(lifted), &mut to);
+        to
+    }
+
+    #[test]
+    fn crate_movers() {
+        assert_eq!(place(&CrateMover9000, "abc", "Z"), stack("Zcba"));
+        assert_eq!(place(&CrateMover9001, "abc", "Z"), stack("Zabc"));
+    }
+
+    #[test]
+    fn limited_lifts_from_the_top() {
+        // e and d come over first, then c and b, then a
+        let crane = Limited { capacity: 2 };
+        assert_eq!(place(&crane, "abcde", "Z"), stack("Zdebca"));
+        assert_eq!(place(&crane, "ab", ""), stack("ab"));
+        let crane = Limited { capacity: 3 };
+        assert_eq!(place(&crane, "abcd", ""), stack("bcda"));
+        assert_eq!(
+            place(&Limited { capacity: 1 }, "abcd", "Z"),
+            place(&CrateMover9000, "abcd", "Z")
+        );
+        assert_eq!(
+            place(&Limited { capacity: 9 }, "abcd", "Z"),
+            place(&CrateMover9001, "abcd", "Z")
+        );
+    }
+
+    #[test]
+    fn rotating_turns_the_top_crates_under() {
+        assert_eq!(place(&Rotating { turns: 1 }, "abc", "Z"), stack("Zcab"));
+        assert_eq!(place(&Rotating { turns: 2 }, "abcd", ""), stack("cdab"));
+        assert_eq!(place(&Rotating { turns: 4 }, "abc", ""), stack("cab"));
+        assert_eq!(place(&Rotating { turns: 3 }, "abc", ""), stack("abc"));
+        assert_eq!(place(&Rotating { turns: 1 }, "", "Z"), stack("Z"));
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(Rotating { turns: 1 }.name(), "rotating crane (1 turn)");
+        assert_eq!(Rotating { turns: 0 }.name(), "rotating crane (0 turns)");
+        assert_eq!(Limited { capacity: 3 }.name(), "limited crane (3 per lift)");
+        assert_eq!(
+            by_name("limited", 0, 1).err().unwrap(),
+            "--capacity must be at least 1"
+        );
+        assert_eq!(by_name("9001", 0, 0).unwrap().name(), "CrateMover 9001");
+    }
+}
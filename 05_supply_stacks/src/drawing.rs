@@ -0,0 +1,238 @@
+//! The drawing of the stacks at the top of the input.
+//!
+//! ```text
+//!     [D]
+//! [N] [C]
+//! [Z] [M] [P]
+//!  1   2   3
+//! ```
+//!
+//! The footer numbers the stacks from 1 and fixes their columns: a crate
+//! belongs to the stack whose number lies under its brackets. Labels are any
+//! run of characters without brackets or whitespace, so `[ab]` and `[12]`
+//! are crates too, and lines may stop early or carry trailing whitespace.
+
+use crate::crane::Stack;
+
+/// A whitespace separated token of a line and its first and last column.
+struct Token<'a> {
+    first: usize,
+    last: usize,
+    text: &'a str,
+}
+
+/// The whitespace separated tokens of `line`, with their columns.
+fn tokens(line: &str) -> Vec<Token<'_>> {
+    let mut found = Vec::new();
+    let mut start = None;
+    let chars = line.char_indices().chain([(line.len(), ' ')]).enumerate();
+    for (column, (byte, c)) in chars {
+        match (start, c.is_whitespace()) {
+            (None, false) => start = Some((column, byte)),
+            (Some((first, from)), true) => {
+                found.push(Token {
+                    first,
+                    last: column - 1,
+                    text: &line[from..byte],
+                });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    found
+}
+
+/// The stacks of a drawing, bottom crate first. `lines` ends with the
+/// footer; `first_line` is the line number of `lines[0]` in the input.
+pub fn parse(lines: &[String], first_line: usize) -> Result<Vec<Stack>, String> {
+    let Some((footer, rows)) = lines.split_last() else {
+        return Err(format!("line {first_line}: missing stack numbers"));
+    };
+    let footer_line = first_line + rows.len();
+    let columns = tokens(footer);
+    for (idx, token) in columns.iter().enumerate() {
+        if token.text.parse::<usize>() != Ok(idx + 1) {
+            return Err(format!(
+                "line {footer_line}: expected stack number {}, found {:?}",
+                idx + 1,
+                token.text
+            ));
+        }
+    }
+    if columns.is_empty() {
+        return Err(format!("line {footer_line}: missing stack numbers"));
+    }
+    let mut stacks = vec![Stack::new(); columns.len()];
+    for (level, (idx, row)) in rows.iter().enumerate().rev().enumerate() {
+        let err = |e: String| format!("line {}: {e}", first_line + idx);
+        for token in tokens(row) {
+            let label = token
+                .text
+                .strip_prefix('[')
+                .and_then(|x| x.strip_suffix(']'))
+                .filter(|x| !x.is_empty() && !x.contains(['[', ']']))
+                .ok_or_else(|| err(format!("expected a crate, found {:?}", token.text)))?;
+            // the stack number under the brackets closest to their middle
+            let middle = token.first + token.last;
+            let stack = columns
+                .iter()
+                .enumerate()
+                .filter(|(_, x)| x.first <= token.last && token.first <= x.last)
+                .min_by_key(|(_, x)| (x.first + x.last).abs_diff(middle))
+                .map(|(number, _)| number)
+                .ok_or_else(|| {
+                    err(format!(
+                        "crate {} at column {} is above no stack number",
+                        token.text,
+                        token.first + 1
+                    ))
+                })?;
+            if stacks[stack].len() != level {
+                return Err(err(format!(
+                    "crate {} of stack {} does not rest on a crate",
+                    token.text,
+                    stack + 1
+                )));
+            }
+            stacks[stack].push(label.to_string());
+        }
+    }
+    Ok(stacks)
+}
+
+/// `text` centered in `width` columns, leaning right.
+fn centered(text: &str, width: usize) -> String {
+    let left = (width - text.chars().count()).div_ceil(2);
+    format!("{:left$}{text:<right$}", "", right = width - left)
+}
+
+/// Columns taken by the crates and the number of every stack.
+pub fn cell_width(stacks: &[Stack]) -> usize {
+    let label = stacks
+        .iter()
+        .flatten()
+        .map(|x| x.chars().count() + 2)
+        .max()
+        .unwrap_or(3);
+    label.max(3).max(stacks.len().to_string().len())
+}
+
+/// The drawing of `stacks`, one line per level and the footer, without
+/// trailing whitespace. `parse` reads it back to the same stacks.
+pub fn draw(stacks: &[Stack]) -> Vec<String> {
+    let width = cell_width(stacks);
+    let height = stacks.iter().map(Vec::len).max().unwrap_or(0);
+    let line = |cells: Vec<String>| cells.join(" ").trim_end().to_string();
+    let mut lines = (0..height)
+        .rev()
+        .map(|level| {
+            line(
+                stacks
+                    .iter()
+                    .map(|stack| match stack.get(level) {
+                        Some(label) => centered(&format!("[{label}]"), width),
+                        None => " ".repeat(width),
+                    })
+                    .collect(),
+            )
+        })
+        .collect::<Vec<_>>();
+    lines.push(line(
+        (1..=stacks.len())
+            .map(|number| centered(&number.to_string(), width))
+            .collect(),
+    ));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    fn stacks(labels: &[&[&str]]) -> Vec<Stack> {
+        labels
+            .iter()
+            .map(|stack| stack.iter().map(|x| x.to_string()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn parses_the_example() {
+        let drawing = lines("    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 ");
+        assert_eq!(
+            parse(&drawing, 1).unwrap(),
+            stacks(&[&["Z", "N"], &["M", "C", "D"], &["P"]])
+        );
+    }
+
+    #[test]
+    fn more_than_nine_stacks() {
+        let drawing = lines(concat!(
+            "                                        [x]\n",
+            "[a] [b] [c] [d] [e] [f] [g] [h] [i] [j] [k]\n",
+            " 1   2   3   4   5   6   7   8   9  10  11",
+        ));
+        let parsed = parse(&drawing, 1).unwrap();
+        assert_eq!(parsed.len(), 11);
+        assert_eq!(parsed[9], stacks(&[&["j"]])[0]);
+        assert_eq!(parsed[10], stacks(&[&["k", "x"]])[0]);
+    }
+
+    #[test]
+    fn multi_character_labels_and_ragged_lines() {
+        let drawing = lines("     [cd]\n[ab] [ef]   \t\n  1    2");
+        assert_eq!(
+            parse(&drawing, 1).unwrap(),
+            stacks(&[&["ab"], &["ef", "cd"]])
+        );
+    }
+
+    #[test]
+    fn draw_reads_back_to_the_same_stacks() {
+        let cases = [
+            stacks(&[&["Z", "N"], &["M", "C", "D"], &["P"]]),
+            stacks(&[&["ab"], &[], &["c", "long", "d"]]),
+            (1..=12)
+                .map(|x| (0..x % 3).map(|y| format!("{x}{y}")).collect())
+                .collect(),
+        ];
+        for original in cases {
+            let drawing = draw(&original);
+            assert!(drawing.iter().all(|x| x.trim_end() == x));
+            assert_eq!(parse(&drawing, 1).unwrap(), original, "{drawing:#?}");
+        }
+    }
+
+    #[test]
+    fn draws_the_puzzle_format() {
+        let drawing = draw(&stacks(&[&["Z", "N"], &["M", "C", "D"], &["P"]]));
+        assert_eq!(drawing, lines("    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3"));
+    }
+
+    #[test]
+    fn errors_name_the_line() {
+        let error = |text: &str| parse(&lines(text), 1).unwrap_err();
+        assert_eq!(
+            error("    [A]\n[B]\n 1   2"),
+            "line 1: crate [A] of stack 2 does not rest on a crate"
+        );
+        assert_eq!(
+            error("[A]\n 1   3"),
+            "line 2: expected stack number 2, found \"3\""
+        );
+        assert_eq!(
+            error("[A] x\n 1   2"),
+            "line 1: expected a crate, found \"x\""
+        );
+        assert_eq!(
+            error("        [A]\n 1   2"),
+            "line 1: crate [A] at column 9 is above no stack number"
+        );
+        assert_eq!(error(""), "line 1: missing stack numbers");
+    }
+}
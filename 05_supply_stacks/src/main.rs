@@ -1,21 +1,24 @@
-use std::collections::VecDeque;
-use std::env;
-use std::fs::File;
-use std::io::BufRead;
-use std::io::BufReader;
+use std::fmt;
 
+use aoc::args::Args;
+use aoc::output::fail;
+use aoc::viz::Frame;
+use aoc::viz::Recorder;
+use aoc::viz::Rgb;
+use crane::Crane;
+use crane::Crate;
+use crane::CrateMover9000;
+use crane::CrateMover9001;
+use crane::Stack;
 use regex::Regex;
 
-fn main() {
-    let filename = env::args().nth(1).expect("Missing filename argument");
-    let file = File::open(filename).expect("File not found");
-    let lines = BufReader::new(file)
-        .lines()
-        .flatten()
-        .collect::<Vec<String>>();
-    pt1(&lines);
-    pt2(&lines);
-}
+mod crane;
+mod drawing;
+
+#[cfg(feature = "embed-inputs")]
+const INPUT: Option<&str> = Some(include_str!("../../05_input.txt"));
+#[cfg(not(feature = "embed-inputs"))]
+const INPUT: Option<&str> = None;
 
 #[derive(Debug)]
 struct Move {
@@ -24,81 +27,281 @@ struct Move {
     to: usize,
 }
 
-fn parse_input(input: &Vec<String>) -> (Vec<VecDeque<char>>, Vec<Move>) {
-    let mut stacks = vec![];
-    let mut moves = vec![];
-    let mut parse_stack = true;
-    let mut st = VecDeque::<Vec<(usize, char)>>::new();
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.count, self.from, self.to)
+    }
+}
+
+/// A move that cannot be made, with the stacks as they were before it.
+#[derive(Debug)]
+struct MoveError {
+    number: usize,
+    movement: String,
+    reason: String,
+    stacks: Vec<Stack>,
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "move {} ({}): {}",
+            self.number, self.movement, self.reason
+        )?;
+        for (idx, stack) in self.stacks.iter().enumerate() {
+            write!(f, "\n  stack {}: {}", idx + 1, join(stack))?;
+        }
+        Ok(())
+    }
+}
+
+/// Why `m` cannot be made on `stacks`, if it cannot.
+fn check_move(stacks: &[Stack], m: &Move) -> Option<String> {
+    let missing = [m.from, m.to]
+        .into_iter()
+        .find(|&x| x == 0 || x > stacks.len());
+    if let Some(stack) = missing {
+        return Some(format!(
+            "there is no stack {stack}, only 1 to {}",
+            stacks.len()
+        ));
+    }
+    if m.from == m.to {
+        return Some(format!("stack {} is both source and target", m.from));
+    }
+    let available = stacks[m.from - 1].len();
+    (available < m.count).then(|| {
+        format!(
+            "stack {} holds {available} crates, {} needed",
+            m.from, m.count
+        )
+    })
+}
+
+fn parse_input(input: &[String]) -> Result<(Vec<Stack>, Vec<Move>), String> {
+    let blank = input
+        .iter()
+        .position(|x| x.trim().is_empty())
+        .unwrap_or(input.len());
+    let stacks = drawing::parse(&input[..blank], 1)?;
     let re_move = Regex::new(r"^move (\d+) from (\d+) to (\d+)$").unwrap();
-    for line in input {
-        if parse_stack {
-            if line.is_empty() {
-                parse_stack = false;
-            } else {
-                let stack_info = line
-                    .chars()
-                    .enumerate()
-                    .filter(|x| ('A'..='Z').contains(&x.1))
-                    .collect::<Vec<(usize, char)>>();
-                if !stack_info.is_empty() {
-                    st.push_front(stack_info);
-                }
-            }
-        } else {
-            let captures = re_move.captures(line).unwrap();
-            moves.push(Move {
-                count: captures[1].parse::<usize>().unwrap(),
-                from: captures[2].parse::<usize>().unwrap(),
-                to: captures[3].parse::<usize>().unwrap(),
-            });
+    let mut moves = vec![];
+    for (idx, line) in input.iter().enumerate().skip(blank + 1) {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
         }
+        let invalid = || format!("line {}: invalid move {line:?}", idx + 1);
+        let captures = re_move.captures(line).ok_or_else(invalid)?;
+        let number = |i: usize| captures[i].parse::<usize>().map_err(|_| invalid());
+        moves.push(Move {
+            count: number(1)?,
+            from: number(2)?,
+            to: number(3)?,
+        });
     }
-    let num_stacks = st.iter().fold(0, |acc, x| acc.max(x.len()));
-    stacks.resize(num_stacks, VecDeque::<char>::new());
-    for stack_info in &st {
-        stack_info
-            .iter()
-            .for_each(|x| stacks[(x.0 - 1) / 4].push_front(x.1));
+    Ok((stacks, moves))
+}
+
+/// The labels of `crates` in a row, separated by spaces unless they are all
+/// single characters.
+fn join<'a>(crates: impl IntoIterator<Item = &'a Crate>) -> String {
+    let crates = crates.into_iter().map(String::as_str).collect::<Vec<_>>();
+    if crates.iter().all(|x| x.chars().count() == 1) {
+        crates.concat()
+    } else {
+        crates.join(" ")
     }
-    (stacks, moves)
 }
 
-fn pt1(input: &Vec<String>) {
-    let (mut stacks, moves) = parse_input(input);
-    moves.iter().for_each(|m| {
-        for _ in 0..m.count {
-            let item = stacks[m.from - 1].pop_front().unwrap();
-            stacks[m.to - 1].push_front(item);
+fn render(stacks: &[Stack], caption: String) -> Frame {
+    let height = stacks.iter().map(|x| x.len()).sum::<usize>();
+    let cell = drawing::cell_width(stacks) + 1;
+    let mut frame = Frame::new(stacks.len() * cell, height + 1).with_caption(caption);
+    for (idx, stack) in stacks.iter().enumerate() {
+        for (level, label) in stack.iter().enumerate() {
+            let first = label.chars().next().unwrap_or('A');
+            let color = aoc::viz::color((first as usize).wrapping_sub('A' as usize));
+            frame.text(idx * cell, height - 1 - level, &format!("[{label}]"), color);
         }
-    });
-    print!("First solution: ");
-    stacks
-        .iter()
-        .filter(|x| x.front().is_some())
-        .for_each(|stack| print!("{}", stack.front().unwrap()));
-    print!("\n");
+        frame.text(idx * cell + 1, height, &(idx + 1).to_string(), Rgb::GREY);
+        frame
+            .details
+            .push(format!("stack {}: {}", idx + 1, join(stack)));
+    }
+    frame
 }
 
-fn get_two_mut<T>(data: &mut [T], a: usize, b: usize) -> (&mut T, &mut T) {
-    assert!(a != b);
-    let ptr: *mut [T] = data;
-    unsafe { (&mut (*ptr)[a], &mut (*ptr)[b]) }
+fn move_caption(crane: &str, idx: usize, moves: &[Move]) -> String {
+    let m = &moves[idx];
+    format!(
+        "{crane} move {}/{}: {} from {} to {}",
+        idx + 1,
+        moves.len(),
+        m.count,
+        m.from,
+        m.to
+    )
 }
 
-fn pt2(input: &Vec<String>) {
-    let (mut stacks, moves) = parse_input(input);
-    moves.iter().for_each(|m| {
-        let (from, to) = get_two_mut(&mut stacks, m.from - 1, m.to - 1);
-        let new_from = from.split_off(m.count);
-        while let Some(x) = from.pop_back() {
-            to.push_front(x);
+/// Runs all the moves with `crane` and returns the final stacks, or the
+/// first move that cannot be made.
+fn rearrange(
+    stacks: &[Stack],
+    moves: &[Move],
+    crane: &dyn Crane,
+    viz: &mut Recorder,
+) -> Result<Vec<Stack>, MoveError> {
+    let mut stacks = stacks.to_vec();
+    let name = crane.name();
+    for (idx, m) in moves.iter().enumerate() {
+        if let Some(reason) = check_move(&stacks, m) {
+            return Err(MoveError {
+                number: idx + 1,
+                movement: m.to_string(),
+                reason,
+                stacks,
+            });
         }
-        *from = new_from;
-    });
-    print!("Second solution: ");
-    stacks
-        .iter()
-        .filter(|x| x.front().is_some())
-        .for_each(|stack| print!("{}", stack.front().unwrap()));
-    print!("\n");
+        let from = &mut stacks[m.from - 1];
+        let lifted = from.split_off(from.len() - m.count);
+        crane.place(lifted, &mut stacks[m.to - 1]);
+        viz.emit(|| render(&stacks, move_caption(&name, idx, moves)));
+    }
+    Ok(stacks)
+}
+
+fn top_crates(stacks: &[Stack]) -> String {
+    join(stacks.iter().filter_map(|x| x.last()))
+}
+
+fn pt1(stacks: &[Stack], moves: &[Move], viz: &mut Recorder, show: bool) {
+    let stacks = rearrange(stacks, moves, &CrateMover9000, viz).unwrap_or_else(|e| fail(e));
+    println!("First solution: {}", top_crates(&stacks));
+    if show {
+        print_drawing(&stacks);
+    }
+}
+
+fn pt2(stacks: &[Stack], moves: &[Move], viz: &mut Recorder, show: bool) {
+    let stacks = rearrange(stacks, moves, &CrateMover9001, viz).unwrap_or_else(|e| fail(e));
+    println!("Second solution: {}", top_crates(&stacks));
+    if show {
+        print_drawing(&stacks);
+    }
+}
+
+fn print_drawing(stacks: &[Stack]) {
+    for line in drawing::draw(stacks) {
+        println!("{line}");
+    }
+}
+
+fn main() {
+    let _stats = aoc::stats::day();
+    let args = Args::from_env();
+    let lines = aoc::stats::phase("read", || aoc::input::lines_from(&args, INPUT));
+    let (stacks, moves) =
+        aoc::stats::phase("parse", || parse_input(&lines)).unwrap_or_else(|e| fail(e));
+    let mut viz = Recorder::from_env();
+    let show = args.flag("drawing");
+    if let Some(name) = args.value("crane") {
+        let capacity = args.parsed("capacity", 3usize);
+        let turns = args.parsed("turns", 1usize);
+        let crane = crane::by_name(name, capacity, turns).unwrap_or_else(|e| fail(e));
+        let stacks = aoc::stats::phase("crane", || {
+            rearrange(&stacks, &moves, crane.as_ref(), &mut viz)
+        })
+        .unwrap_or_else(|e| fail(e));
+        println!("Top crates ({}): {}", crane.name(), top_crates(&stacks));
+        if show {
+            print_drawing(&stacks);
+        }
+        return;
+    }
+    aoc::stats::phase("pt1", || pt1(&stacks, &moves, &mut viz, show));
+    aoc::stats::phase("pt2", || pt2(&stacks, &moves, &mut viz, show));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n\n";
+
+    /// The sample stacks followed by `moves`.
+    fn input(moves: &str) -> (Vec<Stack>, Vec<Move>) {
+        let lines = format!("{SAMPLE}{moves}")
+            .lines()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        parse_input(&lines).unwrap()
+    }
+
+    fn error(moves: &str) -> String {
+        let (stacks, moves) = input(moves);
+        let result = rearrange(&stacks, &moves, &CrateMover9000, &mut Recorder::disabled());
+        result.unwrap_err().to_string()
+    }
+
+    #[test]
+    fn rearranges_the_sample() {
+        let (stacks, moves) =
+            input("move 1 from 2 to 1\nmove 3 from 1 to 3\nmove 2 from 2 to 1\nmove 1 from 1 to 2");
+        let mut viz = Recorder::disabled();
+        let first = rearrange(&stacks, &moves, &CrateMover9000, &mut viz).unwrap();
+        let second = rearrange(&stacks, &moves, &CrateMover9001, &mut viz).unwrap();
+        assert_eq!(
+            (top_crates(&first), top_crates(&second)),
+            ("CMZ".into(), "MCD".into())
+        );
+    }
+
+    #[test]
+    fn stack_zero_does_not_exist() {
+        assert_eq!(
+            error("move 1 from 0 to 1"),
+            "move 1 (move 1 from 0 to 1): there is no stack 0, only 1 to 3\n  \
+             stack 1: ZN\n  stack 2: MCD\n  stack 3: P"
+        );
+    }
+
+    #[test]
+    fn stacks_past_the_last_do_not_exist() {
+        assert_eq!(
+            error("move 1 from 2 to 1\nmove 1 from 1 to 4"),
+            "move 2 (move 1 from 1 to 4): there is no stack 4, only 1 to 3\n  \
+             stack 1: ZND\n  stack 2: MC\n  stack 3: P"
+        );
+    }
+
+    #[test]
+    fn source_and_target_must_differ() {
+        assert_eq!(
+            error("move 1 from 3 to 1\nmove 1 from 2 to 2"),
+            "move 2 (move 1 from 2 to 2): stack 2 is both source and target\n  \
+             stack 1: ZNP\n  stack 2: MCD\n  stack 3: "
+        );
+    }
+
+    #[test]
+    fn too_few_crates() {
+        assert_eq!(
+            error("move 2 from 1 to 3\nmove 3 from 3 to 2\nmove 1 from 3 to 1"),
+            "move 3 (move 1 from 3 to 1): stack 3 holds 0 crates, 1 needed\n  \
+             stack 1: \n  stack 2: MCDZNP\n  stack 3: "
+        );
+        assert_eq!(
+            check_move(
+                &input("").0,
+                &Move {
+                    count: 4,
+                    from: 2,
+                    to: 1
+                }
+            ),
+            Some("stack 2 holds 3 crates, 4 needed".to_string())
+        );
+    }
 }
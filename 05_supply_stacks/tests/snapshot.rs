@@ -0,0 +1,8 @@
+#[test]
+fn stdout_matches_snapshot() {
+    aoc::snapshot::assert_stdout(
+        env!("CARGO_BIN_EXE_supply_stacks"),
+        "05_supply_stacks",
+        cfg!(feature = "embed-inputs"),
+    );
+}
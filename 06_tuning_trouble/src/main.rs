@@ -1,22 +1,19 @@
 use std::collections::HashSet;
 use std::collections::VecDeque;
-use std::env;
-use std::fs::File;
-use std::io::BufRead;
-use std::io::BufReader;
+
+#[cfg(feature = "embed-inputs")]
+const INPUT: Option<&str> = Some(include_str!("../../06_input.txt"));
+#[cfg(not(feature = "embed-inputs"))]
+const INPUT: Option<&str> = None;
 
 fn main() {
-    let filename = env::args().nth(1).expect("Missing filename argument");
-    let file = File::open(filename).expect("File not found");
-    let lines = BufReader::new(file)
-        .lines()
-        .flatten()
-        .collect::<Vec<String>>();
-    pt1(&lines);
-    pt2(&lines);
+    let _stats = aoc::stats::day();
+    let lines = aoc::stats::phase("read", || aoc::input::lines(INPUT));
+    aoc::stats::phase("pt1", || pt1(&lines));
+    aoc::stats::phase("pt2", || pt2(&lines));
 }
 
-fn pt1(input: &Vec<String>) {
+fn pt1(input: &[String]) {
     let mut paket = VecDeque::<char>::new();
     for (idx, c) in input.first().unwrap().chars().enumerate() {
         paket.push_back(c);
@@ -31,7 +28,7 @@ fn pt1(input: &Vec<String>) {
     }
 }
 
-fn pt2(input: &Vec<String>) {
+fn pt2(input: &[String]) {
     let mut paket = VecDeque::<char>::new();
     for (idx, c) in input.first().unwrap().chars().enumerate() {
         paket.push_back(c);
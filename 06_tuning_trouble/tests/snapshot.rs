@@ -0,0 +1,8 @@
+#[test]
+fn stdout_matches_snapshot() {
+    aoc::snapshot::assert_stdout(
+        env!("CARGO_BIN_EXE_tuning_trouble"),
+        "06_tuning_trouble",
+        cfg!(feature = "embed-inputs"),
+    );
+}
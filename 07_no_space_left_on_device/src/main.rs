@@ -1,23 +1,19 @@
-use core::borrow;
 use std::cell::RefCell;
 use std::collections::VecDeque;
-use std::env;
-use std::fs::File;
-use std::io::BufRead;
-use std::io::BufReader;
 use std::rc::Rc;
 use std::str::FromStr;
 
+#[cfg(feature = "embed-inputs")]
+const INPUT: Option<&str> = Some(include_str!("../../07_input.txt"));
+#[cfg(not(feature = "embed-inputs"))]
+const INPUT: Option<&str> = None;
+
 fn main() {
-    let filename = env::args().nth(1).expect("Missing filename argument");
-    let file = File::open(filename).expect("File not found");
-    let lines = BufReader::new(file)
-        .lines()
-        .flatten()
-        .collect::<Vec<String>>();
-    let tree = parse_input(&lines);
-    pt1(&tree);
-    pt2(&tree);
+    let _stats = aoc::stats::day();
+    let lines = aoc::stats::phase("read", || aoc::input::lines(INPUT));
+    let tree = aoc::stats::phase("parse", || parse_input(&lines));
+    aoc::stats::phase("pt1", || pt1(&tree));
+    aoc::stats::phase("pt2", || pt2(&tree));
 }
 
 #[derive(Debug, Clone)]
@@ -34,23 +30,24 @@ impl FromStr for Input {
 
     fn from_str(s: &str) -> Result<Input, ()> {
         if s == "$ ls" {
-            return Ok(Input::ListDirectoryCmd);
+            Ok(Input::ListDirectoryCmd)
         } else if s == "$ cd .." {
-            return Ok(Input::LeaveDirectoryCmd);
+            Ok(Input::LeaveDirectoryCmd)
         } else if s.starts_with("$ cd") {
             let (_, dir_name) = s.split_at(5);
-            return Ok(Input::EnterDirectoryCmd(dir_name.to_string()));
+            Ok(Input::EnterDirectoryCmd(dir_name.to_string()))
         } else if s.starts_with("dir") {
             let (_, dir_name) = s.split_at(4);
-            return Ok(Input::DirectoryEntry(dir_name.to_string()));
+            Ok(Input::DirectoryEntry(dir_name.to_string()))
         } else {
             let (size, name) = s.split_once(' ').unwrap();
             let size = size.parse::<u32>().unwrap();
-            return Ok(Input::FileEntry(name.to_string(), size));
+            Ok(Input::FileEntry(name.to_string(), size))
         }
     }
 }
 
+#[allow(dead_code)]
 #[derive(Debug)]
 enum TreeNode {
     File(u32, String),
@@ -71,7 +68,7 @@ fn compute_sizes(node: Node) -> u32 {
     }
 }
 
-fn parse_input(input: &Vec<String>) -> Node {
+fn parse_input(input: &[String]) -> Node {
     let tree = Rc::new(RefCell::new(TreeNode::Directory(
         "/".to_string(),
         vec![],
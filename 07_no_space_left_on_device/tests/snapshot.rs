@@ -0,0 +1,8 @@
+#[test]
+fn stdout_matches_snapshot() {
+    aoc::snapshot::assert_stdout(
+        env!("CARGO_BIN_EXE_no_space_left_on_device"),
+        "07_no_space_left_on_device",
+        cfg!(feature = "embed-inputs"),
+    );
+}
@@ -1,7 +1,8 @@
-use std::env;
-use std::fs::File;
-use std::io::BufRead;
-use std::io::BufReader;
+#[cfg(feature = "embed-inputs")]
+const INPUT: Option<&str> = Some(include_str!("../../08_input.txt"));
+#[cfg(not(feature = "embed-inputs"))]
+const INPUT: Option<&str> = None;
+
 #[derive(Debug)]
 struct Forrest {
     heights: Vec<Vec<i32>>,
@@ -10,7 +11,7 @@ struct Forrest {
 }
 
 impl Forrest {
-    fn new(lines: &Vec<String>) -> Self {
+    fn new(lines: &[String]) -> Self {
         let num_columns = lines[0].len();
         let num_rows = lines.len();
         let mut heights = vec![vec![0i32; num_rows]; num_columns];
@@ -25,6 +26,7 @@ impl Forrest {
             num_rows,
         }
     }
+    #[allow(clippy::needless_range_loop)]
     fn count_visible_trees(&self) -> usize {
         let mut visibility = vec![vec![false; self.num_rows]; self.num_columns];
         for row_idx in 0..self.num_rows {
@@ -75,7 +77,6 @@ impl Forrest {
         for col_idx in 1..self.num_columns - 1 {
             for row_idx in 1..self.num_rows - 1 {
                 let tree_house_height = self.heights[col_idx][row_idx];
-                println!("{:?}", tree_house_height);
                 let mut up_score = 0;
                 for row_idx_view in (0..row_idx).rev() {
                     up_score += 1;
@@ -104,7 +105,6 @@ impl Forrest {
                         break;
                     }
                 }
-                println!("{left_score}, {right_score}, {up_score}, {down_score}");
                 max_score = max_score.max(left_score * right_score * up_score * down_score);
             }
         }
@@ -113,14 +113,11 @@ impl Forrest {
 }
 
 fn main() {
-    let filename = env::args().nth(1).expect("Missing filename argument");
-    let file = File::open(filename).expect("File not found");
-    let lines = BufReader::new(file)
-        .lines()
-        .flatten()
-        .collect::<Vec<String>>();
-    let f = Forrest::new(&lines);
-    let num_visible_trees = f.count_visible_trees();
+    let _stats = aoc::stats::day();
+    let lines = aoc::stats::phase("read", || aoc::input::lines(INPUT));
+    let f = aoc::stats::phase("parse", || Forrest::new(&lines));
+    let num_visible_trees = aoc::stats::phase("pt1", || f.count_visible_trees());
     println!("First solution {num_visible_trees}");
-    println!("Second solution {}", f.max_scenic_score());
+    let max_scenic_score = aoc::stats::phase("pt2", || f.max_scenic_score());
+    println!("Second solution {max_scenic_score}");
 }
@@ -0,0 +1,8 @@
+#[test]
+fn stdout_matches_snapshot() {
+    aoc::snapshot::assert_stdout(
+        env!("CARGO_BIN_EXE_tree_top_house"),
+        "08_tree_top_house",
+        cfg!(feature = "embed-inputs"),
+    );
+}
@@ -1,13 +1,18 @@
 use std::collections::HashSet;
-use std::env;
-use std::fs::File;
-use std::io::BufRead;
-use std::io::BufReader;
 use std::ops::Add;
 use std::ops::AddAssign;
 use std::ops::Sub;
 use std::str::FromStr;
 
+use aoc::viz::Frame;
+use aoc::viz::Recorder;
+use aoc::viz::Rgb;
+
+#[cfg(feature = "embed-inputs")]
+const INPUT: Option<&str> = Some(include_str!("../../09_input.txt"));
+#[cfg(not(feature = "embed-inputs"))]
+const INPUT: Option<&str> = None;
+
 #[derive(Debug, Copy, Clone)]
 enum Direction {
     Up,
@@ -115,31 +120,79 @@ fn get_two_mut<T>(data: &mut [T], a: usize, b: usize) -> (&mut T, &mut T) {
     unsafe { (&mut (*ptr)[a], &mut (*ptr)[b]) }
 }
 
-fn simulate_rope(len: usize, lines: &Vec<String>) -> HashSet<Pos> {
+const VIEW_WIDTH: i32 = 81;
+const VIEW_HEIGHT: i32 = 41;
+
+fn render(rope: &[Pos], visited: &HashSet<Pos>, caption: String) -> Frame {
+    let mut frame = Frame::new(VIEW_WIDTH as usize, VIEW_HEIGHT as usize).with_caption(caption);
+    let origin = rope[0] - Pos::new(VIEW_WIDTH / 2, -(VIEW_HEIGHT / 2));
+    let to_screen = |pos: Pos| {
+        let x = pos.x - origin.x;
+        let y = origin.y - pos.y;
+        ((0..VIEW_WIDTH).contains(&x) && (0..VIEW_HEIGHT).contains(&y))
+            .then_some((x as usize, y as usize))
+    };
+    for pos in visited {
+        if let Some((x, y)) = to_screen(*pos) {
+            frame.set(x, y, '#', Rgb::GREY);
+        }
+    }
+    for (idx, knot) in rope.iter().enumerate().rev() {
+        if let Some((x, y)) = to_screen(*knot) {
+            let glyph = match idx {
+                0 => 'H',
+                idx if idx == rope.len() - 1 => 'T',
+                idx => char::from_digit(idx as u32, 36).unwrap_or('*'),
+            };
+            frame.set(x, y, glyph, aoc::viz::color(idx));
+        }
+    }
+    for (idx, knot) in rope.iter().enumerate() {
+        frame
+            .details
+            .push(format!("knot {idx}: ({}, {})", knot.x, knot.y));
+    }
+    frame
+        .details
+        .push(format!("tail visited {}", visited.len()));
+    frame
+}
+
+fn simulate_rope(len: usize, lines: &[String], viz: &mut Recorder) -> HashSet<Pos> {
     let mut rope = vec![Pos::default(); len];
     let mut unique_position_counter = HashSet::new();
     unique_position_counter.insert(*rope.last().unwrap());
-    for line in lines {
+    for (line_idx, line) in lines.iter().enumerate() {
         let mov = line.parse::<Move>().unwrap();
-        for _ in 0..mov.count {
+        for step in 0..mov.count {
             rope[0] += Pos::from(mov.dir);
             for idx in 1..rope.len() {
                 let (head, tail) = get_two_mut(&mut rope, idx - 1, idx);
-                *tail += move_tail(&tail, &head);
+                *tail += move_tail(tail, head);
             }
             unique_position_counter.insert(*rope.last().unwrap());
+            viz.emit(|| {
+                let caption = format!(
+                    "{len} knots, move {}/{}: {line} ({}/{}), {} visited",
+                    line_idx + 1,
+                    lines.len(),
+                    step + 1,
+                    mov.count,
+                    unique_position_counter.len()
+                );
+                render(&rope, &unique_position_counter, caption)
+            });
         }
     }
     unique_position_counter
 }
 
 fn main() {
-    let filename = env::args().nth(1).expect("Missing filename argument");
-    let file = File::open(filename).expect("File not found");
-    let lines = BufReader::new(file)
-        .lines()
-        .flatten()
-        .collect::<Vec<String>>();
-    println!("First solution {}", simulate_rope(2, &lines).len());
-    println!("Second solution {}", simulate_rope(10, &lines).len());
+    let _stats = aoc::stats::day();
+    let lines = aoc::stats::phase("read", || aoc::input::lines(INPUT));
+    let mut viz = Recorder::from_env();
+    let visited = aoc::stats::phase("pt1", || simulate_rope(2, &lines, &mut viz).len());
+    println!("First solution {visited}");
+    let visited = aoc::stats::phase("pt2", || simulate_rope(10, &lines, &mut viz).len());
+    println!("Second solution {visited}");
 }
@@ -0,0 +1,8 @@
+#[test]
+fn stdout_matches_snapshot() {
+    aoc::snapshot::assert_stdout(
+        env!("CARGO_BIN_EXE_rope_bridge"),
+        "09_rope_bridge",
+        cfg!(feature = "embed-inputs"),
+    );
+}
@@ -1,10 +1,15 @@
-use std::env;
-use std::fs::File;
-use std::io::BufRead;
-use std::io::BufReader;
 use std::str::FromStr;
 
-#[derive(Copy, Clone)]
+use aoc::viz::Frame;
+use aoc::viz::Recorder;
+use aoc::viz::Rgb;
+
+#[cfg(feature = "embed-inputs")]
+const INPUT: Option<&str> = Some(include_str!("../../10_input.txt"));
+#[cfg(not(feature = "embed-inputs"))]
+const INPUT: Option<&str> = None;
+
+#[derive(Debug, Copy, Clone)]
 enum Op {
     Noop,
     Addx(i32),
@@ -23,7 +28,7 @@ impl FromStr for Op {
 }
 
 #[derive(Clone)]
-struct CPU {
+struct Cpu {
     cycle: u32,
     reg_x: i32,
     program: Vec<Op>,
@@ -32,8 +37,8 @@ struct CPU {
     signal_strength: i32,
 }
 
-impl CPU {
-    fn new(program_text: &Vec<String>) -> Self {
+impl Cpu {
+    fn new(program_text: &[String]) -> Self {
         let program = program_text
             .iter()
             .map(|x| x.parse().unwrap())
@@ -67,13 +72,13 @@ impl CPU {
     }
 }
 
-struct CRT {
+struct Crt {
     pixels: Vec<Vec<char>>,
-    cpu: CPU,
+    cpu: Cpu,
 }
 
-impl CRT {
-    fn new(cpu: CPU) -> Self {
+impl Crt {
+    fn new(cpu: Cpu) -> Self {
         Self {
             pixels: vec![vec!['.'; 40]; 6],
             cpu,
@@ -86,39 +91,71 @@ impl CRT {
         }
     }
 
-    fn update_screen(&mut self) {
-        for row in &mut self.pixels.iter_mut() {
-            for (col_idx, col) in &mut row.iter_mut().enumerate() {
-                if self.cpu.reg_x.abs_diff(col_idx as i32) <= 1 {
-                    *col = '#';
-                }
-                self.cpu.run_cycle();
+    fn render(&self, beam: (usize, usize)) -> Frame {
+        let width = self.pixels[0].len();
+        let mut frame = Frame::new(width, self.pixels.len() + 2)
+            .with_caption(format!("cycle {} X={}", self.cpu.cycle, self.cpu.reg_x));
+        for (row_idx, row) in self.pixels.iter().enumerate() {
+            for (col_idx, c) in row.iter().enumerate() {
+                let color = if *c == '#' { Rgb::GREEN } else { Rgb::GREY };
+                frame.set(col_idx, row_idx, *c, color);
             }
         }
+        frame.set(beam.1, beam.0, '@', Rgb::YELLOW);
+        frame.details = vec![
+            format!("cycle: {}", self.cpu.cycle),
+            format!("X: {}", self.cpu.reg_x),
+            format!("sprite: {}..={}", self.cpu.reg_x - 1, self.cpu.reg_x + 1),
+            format!(
+                "instruction {}: {:?}",
+                self.cpu.instr_ptr,
+                self.cpu.program.get(self.cpu.instr_ptr)
+            ),
+            format!("waiting for addx: {}", self.cpu.wait_cycle),
+            format!("beam: row {} col {}", beam.0, beam.1),
+        ];
+        for col_idx in self.cpu.reg_x - 1..=self.cpu.reg_x + 1 {
+            if (0..width as i32).contains(&col_idx) {
+                frame.set(col_idx as usize, self.pixels.len() + 1, '#', Rgb::BLUE);
+            }
+        }
+        frame
+    }
+
+    fn update_screen(&mut self, viz: &mut Recorder) {
+        let (height, width) = (self.pixels.len(), self.pixels[0].len());
+        for pixel_idx in 0..height * width {
+            let (row_idx, col_idx) = (pixel_idx / width, pixel_idx % width);
+            if self.cpu.reg_x.abs_diff(col_idx as i32) <= 1 {
+                self.pixels[row_idx][col_idx] = '#';
+            }
+            viz.emit(|| self.render((row_idx, col_idx)));
+            self.cpu.run_cycle();
+        }
     }
 }
 
 fn main() {
-    let filename = env::args().nth(1).expect("Missing filename argument");
-    let file = File::open(filename).expect("File not found");
-    let lines = BufReader::new(file)
-        .lines()
-        .flatten()
-        .collect::<Vec<String>>();
-    let probe_cycles = vec![20u32, 60, 100, 140, 180, 220];
-    let mut cpu1 = CPU::new(&lines);
+    let _stats = aoc::stats::day();
+    let lines = aoc::stats::phase("read", || aoc::input::lines(INPUT));
+    let probe_cycles = [20u32, 60, 100, 140, 180, 220];
+    let mut cpu1 = aoc::stats::phase("parse", || Cpu::new(&lines));
     let cpu2 = cpu1.clone();
-    let mut res = 0;
-    while cpu1.cycle <= *probe_cycles.last().unwrap() {
-        if probe_cycles.contains(&cpu1.cycle) {
-            cpu1.run_cycle();
-            res += cpu1.signal_strength;
-        } else {
-            cpu1.run_cycle();
+    let res = aoc::stats::phase("pt1", || {
+        let mut res = 0;
+        while cpu1.cycle <= *probe_cycles.last().unwrap() {
+            if probe_cycles.contains(&cpu1.cycle) {
+                cpu1.run_cycle();
+                res += cpu1.signal_strength;
+            } else {
+                cpu1.run_cycle();
+            }
         }
-    }
+        res
+    });
     println!("First Solution {res}");
-    let mut crt = CRT::new(cpu2);
-    crt.update_screen();
+    let mut crt = Crt::new(cpu2);
+    let mut viz = Recorder::from_env();
+    aoc::stats::phase("pt2", || crt.update_screen(&mut viz));
     crt.display();
 }
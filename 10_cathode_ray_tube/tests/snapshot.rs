@@ -0,0 +1,8 @@
+#[test]
+fn stdout_matches_snapshot() {
+    aoc::snapshot::assert_stdout(
+        env!("CARGO_BIN_EXE_cathode_ray_tube"),
+        "10_cathode_ray_tube",
+        cfg!(feature = "embed-inputs"),
+    );
+}
@@ -1,9 +1,14 @@
-use std::env;
-use std::fs::File;
-use std::io::BufRead;
-use std::io::BufReader;
 use std::str::FromStr;
 
+use aoc::viz::Frame;
+use aoc::viz::Recorder;
+use aoc::viz::Rgb;
+
+#[cfg(feature = "embed-inputs")]
+const INPUT: Option<&str> = Some(include_str!("../../11_input.txt"));
+#[cfg(not(feature = "embed-inputs"))]
+const INPUT: Option<&str> = None;
+
 #[derive(Debug, Copy, Clone)]
 enum Op {
     Add(i64),
@@ -108,14 +113,43 @@ struct Circus {
 }
 
 impl Circus {
-    fn new(lines: &Vec<String>) -> Self {
+    fn new(lines: &[String]) -> Self {
         let mut monkeys = Vec::new();
         for idx in 0..lines.len() / 6 {
             monkeys.push(Monkey::new(&lines[idx * 6..(idx + 1) * 6]));
         }
         Self { monkeys }
     }
-    fn do_round<F>(&mut self, op: F)
+    fn render(&self, active: usize, caption: String) -> Frame {
+        let mut frame = Frame::new(120, self.monkeys.len()).with_caption(caption);
+        for (idx, monkey) in self.monkeys.iter().enumerate() {
+            let color = if idx == active {
+                Rgb::YELLOW
+            } else {
+                Rgb::GREY
+            };
+            let label = format!("Monkey {idx} ({:>6}):", monkey.inspection_count);
+            frame.text(0, idx, &label, color);
+            let mut x = label.len() + 1;
+            for item in &monkey.items {
+                let item = item.to_string();
+                frame.text(x, idx, &item, aoc::viz::color(idx));
+                x += item.len() + 1;
+            }
+            frame.details.push(format!(
+                "monkey {idx}: inspected {}, new = {:?}, divisible by {} ? {} : {}, items {:?}",
+                monkey.inspection_count,
+                monkey.op,
+                monkey.div_by,
+                monkey.first_target,
+                monkey.second_target,
+                monkey.items
+            ));
+        }
+        frame
+    }
+
+    fn do_round<F>(&mut self, op: F, viz: &mut Recorder, label: &str, round: usize)
     where
         F: Fn(i64) -> i64 + Copy,
     {
@@ -126,13 +160,14 @@ impl Circus {
             let b_idx = monkey.second_target;
             self.monkeys.get_mut(a_idx).unwrap().items.append(&mut a);
             self.monkeys.get_mut(b_idx).unwrap().items.append(&mut b);
+            viz.emit(|| self.render(idx, format!("{label} round {round}, monkey {idx}")));
         }
     }
 }
 
-fn pt1(mut circus: Circus) {
-    for _ in 0..20 {
-        circus.do_round(|x| x / 3);
+fn pt1(mut circus: Circus, viz: &mut Recorder) {
+    for round in 0..20 {
+        circus.do_round(|x| x / 3, viz, "relaxed", round + 1);
     }
     let mut important_monkeys = circus
         .monkeys
@@ -147,15 +182,15 @@ fn pt1(mut circus: Circus) {
     println!("Level of monkey business (relaxed) = {monkey_business}");
 }
 
-fn pt2(mut circus: Circus) {
+fn pt2(mut circus: Circus, viz: &mut Recorder) {
     let module = circus
         .monkeys
         .iter()
         .map(|x| x.div_by)
         .reduce(|acc, x| acc * x)
         .unwrap();
-    for _ in 0..10000 {
-        circus.do_round(|x| x % module);
+    for round in 0..10000 {
+        circus.do_round(|x| x % module, viz, "stressed", round + 1);
     }
     let mut important_monkeys = circus
         .monkeys
@@ -172,14 +207,14 @@ fn pt2(mut circus: Circus) {
 }
 
 fn main() {
-    let filename = env::args().nth(1).expect("Missing filename argument");
-    let file = File::open(filename).expect("File not found");
-    let lines = BufReader::new(file)
-        .lines()
-        .flatten()
-        .filter(|x| !x.is_empty())
-        .collect::<Vec<String>>();
-    let circus = Circus::new(&lines);
-    pt1(circus.clone());
-    pt2(circus);
+    let _stats = aoc::stats::day();
+    let lines = aoc::stats::phase("read", || {
+        let mut lines = aoc::input::lines(INPUT);
+        lines.retain(|x| !x.is_empty());
+        lines
+    });
+    let circus = aoc::stats::phase("parse", || Circus::new(&lines));
+    let mut viz = Recorder::from_env();
+    aoc::stats::phase("pt1", || pt1(circus.clone(), &mut viz));
+    aoc::stats::phase("pt2", || pt2(circus, &mut viz));
 }
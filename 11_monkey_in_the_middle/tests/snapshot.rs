@@ -0,0 +1,8 @@
+#[test]
+fn stdout_matches_snapshot() {
+    aoc::snapshot::assert_stdout(
+        env!("CARGO_BIN_EXE_monkey_in_the_middle"),
+        "11_monkey_in_the_middle",
+        cfg!(feature = "embed-inputs"),
+    );
+}
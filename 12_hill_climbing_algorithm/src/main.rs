@@ -1,8 +1,13 @@
 use std::collections::VecDeque;
-use std::env;
-use std::fs::File;
-use std::io::BufRead;
-use std::io::BufReader;
+
+use aoc::viz::Frame;
+use aoc::viz::Recorder;
+use aoc::viz::Rgb;
+
+#[cfg(feature = "embed-inputs")]
+const INPUT: Option<&str> = Some(include_str!("../../12_input.txt"));
+#[cfg(not(feature = "embed-inputs"))]
+const INPUT: Option<&str> = None;
 
 #[derive(Debug, Clone)]
 struct Map {
@@ -15,22 +20,21 @@ struct Map {
 }
 
 impl Map {
-    fn new(lines: &Vec<String>) -> Self {
+    fn new(lines: &[String]) -> Self {
         let height = lines.len() as u32;
         let width = lines[0].len() as u32;
-        let mut height_data = Vec::new();
-        height_data.reserve(height as usize);
+        let mut height_data = Vec::with_capacity(height as usize);
         for line in lines {
             height_data.push(Vec::from_iter(line.chars().map(|c| match c {
-                ('a'..='z') => c as u8 - 'a' as u8,
+                ('a'..='z') => c as u8 - b'a',
                 'S' => 0,
-                'E' => 'z' as u8 - 'a' as u8,
+                'E' => b'z' - b'a',
                 _ => panic!(),
             })));
         }
         let mut start = (0, 0);
         let mut goal = (0, 0);
-        for (flat_idx, c) in lines.iter().map(|x| x.chars()).flatten().enumerate() {
+        for (flat_idx, c) in lines.iter().flat_map(|x| x.chars()).enumerate() {
             match c {
                 'S' => {
                     start = (
@@ -108,19 +112,60 @@ impl Map {
         self.height_data[y as usize][x as usize]
     }
 
-    fn lowest_cost_from_start(mut self) -> u32 {
+    fn render(&self, current: (i32, i32), queued: &VecDeque<(i32, i32)>, caption: String) -> Frame {
+        let mut frame = Frame::new(self.width as usize, self.height as usize).with_caption(caption);
+        for y in 0..self.height as i32 {
+            for x in 0..self.width as i32 {
+                let height = self.h((x, y));
+                let color = if self.dist((x, y)) == u32::MAX {
+                    let shade = 60 + height * 7;
+                    Rgb(shade, shade, shade)
+                } else {
+                    Rgb(0, 80 + height * 6, 255 - height * 6)
+                };
+                frame.set(x as usize, y as usize, (b'a' + height) as char, color);
+            }
+        }
+        for (x, y) in queued.iter().chain([&current]) {
+            let glyph = (b'a' + self.h((*x, *y))) as char;
+            frame.set(*x as usize, *y as usize, glyph, Rgb::YELLOW);
+        }
+        frame.set(self.start.0 as usize, self.start.1 as usize, 'S', Rgb::RED);
+        frame.set(self.goal.0 as usize, self.goal.1 as usize, 'E', Rgb::RED);
+        let visited = self
+            .distance_data
+            .iter()
+            .flatten()
+            .filter(|x| **x != u32::MAX);
+        frame.details = vec![
+            format!("current: {current:?} at distance {}", self.dist(current)),
+            format!("frontier: {} positions", queued.len() + 1),
+            format!("visited: {} positions", visited.count()),
+            format!(
+                "goal {:?}: distance {:?}",
+                self.goal,
+                Some(self.dist(self.goal)).filter(|x| *x != u32::MAX)
+            ),
+        ];
+        frame
+    }
+
+    fn lowest_cost_from_start(mut self, viz: &mut Recorder) -> u32 {
         *self.dist_mut(self.start) = 0;
         let mut to_visit = VecDeque::new();
         to_visit.push_back(self.start);
 
+        let mut layer = 0;
         while let Some(pos) = to_visit.pop_front() {
             let distance = self.dist(pos) + 1;
-            for n in self.accessible_neighbors(pos) {
-                if let Some(n) = n {
-                    if distance < self.dist(n) {
-                        *self.dist_mut(n) = distance;
-                        to_visit.push_back(n);
-                    }
+            if distance > layer {
+                layer = distance;
+                viz.emit(|| self.render(pos, &to_visit, format!("BFS distance {}", distance - 1)));
+            }
+            for n in self.accessible_neighbors(pos).into_iter().flatten() {
+                if distance < self.dist(n) {
+                    *self.dist_mut(n) = distance;
+                    to_visit.push_back(n);
                 }
             }
         }
@@ -145,21 +190,24 @@ impl Map {
             .collect::<Vec<_>>()
     }
 
-    fn scenic_route_cost(mut self) -> u32 {
+    fn scenic_route_cost(mut self, viz: &mut Recorder) -> u32 {
         let mut to_visit = VecDeque::new();
         for pos in self.all_zero_heights() {
             *self.dist_mut(pos) = 0;
             to_visit.push_back(pos);
         }
 
+        let mut layer = 0;
         while let Some(pos) = to_visit.pop_front() {
             let distance = self.dist(pos) + 1;
-            for n in self.accessible_neighbors(pos) {
-                if let Some(n) = n {
-                    if distance < self.dist(n) {
-                        *self.dist_mut(n) = distance;
-                        to_visit.push_back(n);
-                    }
+            if distance > layer {
+                layer = distance;
+                viz.emit(|| self.render(pos, &to_visit, format!("BFS distance {}", distance - 1)));
+            }
+            for n in self.accessible_neighbors(pos).into_iter().flatten() {
+                if distance < self.dist(n) {
+                    *self.dist_mut(n) = distance;
+                    to_visit.push_back(n);
                 }
             }
         }
@@ -168,16 +216,16 @@ impl Map {
 }
 
 fn main() {
-    let filename = env::args().nth(1).expect("Missing filename argument");
-    let file = File::open(filename).expect("File not found");
-    let lines = BufReader::new(file)
-        .lines()
-        .flatten()
-        .filter(|x| !x.is_empty())
-        .collect::<Vec<String>>();
-    let map = Map::new(&lines);
-    let cost = map.clone().lowest_cost_from_start();
+    let _stats = aoc::stats::day();
+    let lines = aoc::stats::phase("read", || {
+        let mut lines = aoc::input::lines(INPUT);
+        lines.retain(|x| !x.is_empty());
+        lines
+    });
+    let map = aoc::stats::phase("parse", || Map::new(&lines));
+    let mut viz = Recorder::from_env();
+    let cost = aoc::stats::phase("pt1", || map.clone().lowest_cost_from_start(&mut viz));
     println!("Distance {cost}");
-    let cost = map.scenic_route_cost();
+    let cost = aoc::stats::phase("pt2", || map.scenic_route_cost(&mut viz));
     println!("Scenic route {cost}");
 }
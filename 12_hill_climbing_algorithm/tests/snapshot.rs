@@ -0,0 +1,8 @@
+#[test]
+fn stdout_matches_snapshot() {
+    aoc::snapshot::assert_stdout(
+        env!("CARGO_BIN_EXE_hill_climbing_algorithm"),
+        "12_hill_climbing_algorithm",
+        cfg!(feature = "embed-inputs"),
+    );
+}
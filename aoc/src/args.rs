@@ -0,0 +1,66 @@
+//! Command line arguments of the day binaries.
+//!
+//! Options are written as `--name=value` or as a bare `--flag`; everything
+//! else is positional, the first positional argument being the input file.
+
+use std::env;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::output::fail;
+
+#[derive(Debug, Clone, Default)]
+pub struct Args {
+    pub positional: Vec<String>,
+    options: Vec<(String, Option<String>)>,
+}
+
+impl Args {
+    pub fn from_env() -> Self {
+        Self::parse(env::args().skip(1))
+    }
+
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Self {
+        let mut parsed = Self::default();
+        for arg in args {
+            match arg.strip_prefix("--") {
+                Some(option) => match option.split_once('=') {
+                    Some((name, value)) => parsed
+                        .options
+                        .push((name.to_string(), Some(value.to_string()))),
+                    None => parsed.options.push((option.to_string(), None)),
+                },
+                None => parsed.positional.push(arg),
+            }
+        }
+        parsed
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.options.iter().any(|(x, _)| x == name)
+    }
+
+    /// The value of the last `--name=value`, if any.
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(x, _)| x == name)
+            .and_then(|(_, value)| value.as_deref())
+    }
+
+    /// The parsed value of `--name`, or `default` without one. An invalid
+    /// value is reported through [`fail`].
+    pub fn parsed<T>(&self, name: &str, default: T) -> T
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        match self.value(name) {
+            Some(value) => value
+                .parse()
+                .unwrap_or_else(|e| fail(format!("invalid value {value:?} for --{name}: {e}"))),
+            None => default,
+        }
+    }
+}
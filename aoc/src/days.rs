@@ -0,0 +1,62 @@
+//! Registry of the puzzle days in this workspace.
+
+use std::env;
+use std::io;
+use std::path::PathBuf;
+
+use crate::workspace_root;
+
+pub struct Day {
+    pub number: u32,
+    /// Name of the package and its binary; the crate lives in `NN_<bin>`.
+    pub bin: &'static str,
+    /// Keep every n-th visualization frame when recording the day, or `None`
+    /// if the day does not emit frames.
+    pub frame_every: Option<usize>,
+}
+
+impl Day {
+    const fn new(number: u32, bin: &'static str) -> Self {
+        Self {
+            number,
+            bin,
+            frame_every: None,
+        }
+    }
+
+    const fn simulation(number: u32, bin: &'static str, frame_every: usize) -> Self {
+        Self {
+            number,
+            bin,
+            frame_every: Some(frame_every),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        format!("{:02}_{}", self.number, self.bin)
+    }
+
+    pub fn input(&self) -> PathBuf {
+        workspace_root().join(format!("{:02}_input.txt", self.number))
+    }
+
+    /// The day's binary, built into the same directory as the running one.
+    pub fn executable(&self) -> io::Result<PathBuf> {
+        Ok(env::current_exe()?.with_file_name(format!("{}{}", self.bin, env::consts::EXE_SUFFIX)))
+    }
+}
+
+pub const DAYS: [Day; 12] = [
+    Day::new(1, "calories"),
+    Day::new(2, "rock_paper_scissors"),
+    Day::new(3, "rucksack_reorg"),
+    Day::new(4, "camp_cleanup"),
+    Day::simulation(5, "supply_stacks", 1),
+    Day::new(6, "tuning_trouble"),
+    Day::new(7, "no_space_left_on_device"),
+    Day::new(8, "tree_top_house"),
+    Day::simulation(9, "rope_bridge", 10),
+    Day::simulation(10, "cathode_ray_tube", 1),
+    Day::simulation(11, "monkey_in_the_middle", 8),
+    Day::simulation(12, "hill_climbing_algorithm", 4),
+];
@@ -0,0 +1,43 @@
+//! Loading the puzzle input of a day.
+//!
+//! Days built with their `embed-inputs` feature carry `NN_input.txt` in the
+//! binary and only read a file when one is passed as the first positional
+//! argument.
+
+use std::fs::File;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::path::Path;
+
+use crate::args::Args;
+
+pub fn read_lines(path: impl AsRef<Path>) -> Vec<String> {
+    let file = File::open(path).expect("File not found");
+    BufReader::new(file).lines().map_while(Result::ok).collect()
+}
+
+/// Lines of the file named by the first positional argument, or of
+/// `embedded` if no file was given.
+pub fn lines(embedded: Option<&'static str>) -> Vec<String> {
+    lines_from(&Args::from_env(), embedded)
+}
+
+pub fn lines_from(args: &Args, embedded: Option<&'static str>) -> Vec<String> {
+    match (args.positional.first(), embedded) {
+        (Some(filename), _) => read_lines(filename),
+        (None, Some(input)) => input.lines().map(str::to_string).collect(),
+        (None, None) => panic!("Missing filename argument"),
+    }
+}
+
+/// Name and lines of every file named by the positional arguments, or of
+/// `embedded` (named `embedded`) if no file was given.
+pub fn inputs_from(args: &Args, embedded: Option<&'static str>) -> Vec<(String, Vec<String>)> {
+    if args.positional.is_empty() {
+        return vec![("embedded".to_string(), lines_from(args, embedded))];
+    }
+    args.positional
+        .iter()
+        .map(|filename| (filename.clone(), read_lines(filename)))
+        .collect()
+}
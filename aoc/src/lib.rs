@@ -0,0 +1,20 @@
+//! Shared helpers for the daily puzzle binaries.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+pub mod args;
+pub mod days;
+pub mod input;
+pub mod output;
+pub mod rng;
+pub mod snapshot;
+pub mod stats;
+pub mod viz;
+
+pub fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("aoc crate lives inside the workspace")
+        .to_path_buf()
+}
@@ -0,0 +1,118 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::io::BufRead;
+use std::io::Write;
+use std::process;
+use std::process::Command;
+
+use aoc::days::Day;
+use aoc::days::DAYS;
+use aoc::snapshot;
+
+mod tui;
+
+const USAGE: &str = "usage: aoc <run <--all|DAY...> | tui | snapshot <review|accept|reject>>";
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+    let result = match args.as_slice() {
+        ["run", "--all"] => run(DAYS.iter().collect()),
+        ["run", days @ ..] if !days.is_empty() => match select_days(days) {
+            Some(days) => run(days),
+            None => {
+                eprintln!("{USAGE}");
+                process::exit(2);
+            }
+        },
+        ["tui"] => tui::run(),
+        ["snapshot", "review"] => review(),
+        ["snapshot", "accept"] => for_each_pending(snapshot::accept),
+        ["snapshot", "reject"] => for_each_pending(snapshot::reject),
+        _ => {
+            eprintln!("{USAGE}");
+            process::exit(2);
+        }
+    };
+    if let Err(e) = result {
+        eprintln!("error: {e}");
+        process::exit(1);
+    }
+}
+
+fn select_days(numbers: &[&str]) -> Option<Vec<&'static Day>> {
+    numbers
+        .iter()
+        .map(|x| {
+            let number = x.parse::<u32>().ok()?;
+            DAYS.iter().find(|day| day.number == number)
+        })
+        .collect()
+}
+
+/// Runs the selected days in order on their input file; days built with
+/// `embed-inputs` fall back to their built-in input when there is none.
+fn run(days: Vec<&Day>) -> io::Result<()> {
+    for day in days {
+        println!("== {}", day.name());
+        let exe = day.executable()?;
+        if !exe.exists() {
+            return Err(io::Error::other(format!("{} not built", exe.display())));
+        }
+        let mut command = Command::new(exe);
+        if day.input().exists() {
+            command.arg(day.input());
+        }
+        let status = command.status()?;
+        if !status.success() {
+            return Err(io::Error::other(format!(
+                "{} exited with {status}",
+                day.name()
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn for_each_pending(action: fn(&std::path::Path) -> io::Result<()>) -> io::Result<()> {
+    for pending in snapshot::pending()? {
+        action(&pending)?;
+        println!("{}", snapshot::snapshot_path(&pending).display());
+    }
+    Ok(())
+}
+
+fn review() -> io::Result<()> {
+    let pending = snapshot::pending()?;
+    if pending.is_empty() {
+        println!("No pending snapshots");
+        return Ok(());
+    }
+    let mut stdin = io::stdin().lock();
+    for pending in pending {
+        let target = snapshot::snapshot_path(&pending);
+        let actual = fs::read_to_string(&pending)?;
+        println!("=== {}", target.display());
+        match fs::read_to_string(&target) {
+            Ok(expected) => print!("{}", snapshot::diff(&expected, &actual)),
+            Err(_) => print!("{}", snapshot::diff("", &actual)),
+        }
+        loop {
+            print!("[a]ccept, [r]eject, [s]kip? ");
+            io::stdout().flush()?;
+            let mut answer = String::new();
+            if stdin.read_line(&mut answer)? == 0 {
+                return Ok(());
+            }
+            match answer.trim() {
+                "a" => snapshot::accept(&pending)?,
+                "r" => snapshot::reject(&pending)?,
+                "s" => {}
+                _ => continue,
+            }
+            break;
+        }
+    }
+    Ok(())
+}
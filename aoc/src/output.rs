@@ -0,0 +1,68 @@
+//! Writing results and reporting errors from the day binaries.
+
+use std::fmt::Display;
+use std::fs;
+
+/// Writes `content` to `path`, or to stdout if `path` is `-`.
+pub fn export(path: &str, content: String) {
+    if path == "-" {
+        print!("{content}");
+    } else {
+        fs::write(path, content).expect("Failed to write export");
+    }
+}
+
+/// `text` as a CSV field, quoted when it holds a separator, quote or line
+/// break.
+pub fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// `text` as a JSON string literal.
+pub fn json_string(text: &str) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => out += "\\\"",
+            '\\' => out += "\\\\",
+            '\n' => out += "\\n",
+            '\r' => out += "\\r",
+            '\t' => out += "\\t",
+            c if c < ' ' => out += &format!("\\u{:04x}", c as u32),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Reports an invalid input or option and exits with status 1, after the
+/// totals of the day.
+pub fn fail(error: impl Display) -> ! {
+    eprintln!("error: {error}");
+    crate::stats::exit(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_fields_are_quoted_when_needed() {
+        assert_eq!(csv_field("Rock"), "Rock");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn json_strings_are_escaped() {
+        assert_eq!(json_string("elf"), "\"elf\"");
+        assert_eq!(json_string("a\"b\\c"), "\"a\\\"b\\\\c\"");
+        assert_eq!(json_string("\t\n\u{1}"), "\"\\t\\n\\u0001\"");
+    }
+}
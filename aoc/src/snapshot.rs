@@ -0,0 +1,148 @@
+//! Golden snapshots of each day binary's stdout.
+//!
+//! Snapshots live in `snapshots/<day>.stdout` at the workspace root. A failed
+//! comparison leaves the actual output next to the snapshot as
+//! `<day>.stdout.new`, which `aoc snapshot review` (or `accept`) promotes.
+//! Running the tests with `AOC_UPDATE_SNAPSHOTS=1` accepts changes directly.
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+
+use crate::workspace_root;
+
+const SNAPSHOT_EXT: &str = "stdout";
+const PENDING_EXT: &str = "new";
+
+pub fn snapshot_dir() -> PathBuf {
+    workspace_root().join("snapshots")
+}
+
+/// Runs `bin` on the input of `day` (e.g. `"01_calories"`) and compares its
+/// stdout against the stored snapshot, panicking with a diff on mismatch.
+/// `embedded` binaries run on their built-in input, so each day's test
+/// passes its own `cfg!(feature = "embed-inputs")`.
+pub fn assert_stdout(bin: &str, day: &str, embedded: bool) {
+    let root = workspace_root();
+    let (number, _) = day.split_once('_').expect("Day name must be NN_name");
+    let input = root.join(format!("{number}_input.txt"));
+    let mut command = Command::new(bin);
+    if !embedded {
+        command.arg(&input);
+    }
+    let output = command
+        .current_dir(&root)
+        .output()
+        .expect("Failed to run day binary");
+    assert!(
+        output.status.success(),
+        "{day} exited with {}:\n{}",
+        output.status,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let actual = String::from_utf8(output.stdout).expect("stdout is not UTF-8");
+
+    let snapshot = snapshot_dir().join(format!("{day}.{SNAPSHOT_EXT}"));
+    let pending = pending_path(&snapshot);
+    let expected = fs::read_to_string(&snapshot).ok();
+    if expected.as_deref() == Some(actual.as_str()) {
+        remove_if_exists(&pending).unwrap();
+        return;
+    }
+    if env::var("AOC_UPDATE_SNAPSHOTS").is_ok_and(|x| x == "1") {
+        fs::create_dir_all(snapshot_dir()).unwrap();
+        fs::write(&snapshot, &actual).unwrap();
+        remove_if_exists(&pending).unwrap();
+        return;
+    }
+    fs::create_dir_all(snapshot_dir()).unwrap();
+    fs::write(&pending, &actual).unwrap();
+    match expected {
+        None => panic!(
+            "No snapshot for {day}, wrote {}\nRun `cargo run -p aoc -- snapshot review` to accept it",
+            pending.display()
+        ),
+        Some(expected) => panic!(
+            "stdout of {day} differs from its snapshot:\n{}\nRun `cargo run -p aoc -- snapshot review` to accept it",
+            diff(&expected, &actual)
+        ),
+    }
+}
+
+/// All snapshots with a pending `.new` candidate, sorted by day.
+pub fn pending() -> io::Result<Vec<PathBuf>> {
+    let mut pending = Vec::new();
+    let entries = match fs::read_dir(snapshot_dir()) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(pending),
+        Err(e) => return Err(e),
+    };
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_some_and(|x| x == PENDING_EXT) {
+            pending.push(path);
+        }
+    }
+    pending.sort();
+    Ok(pending)
+}
+
+pub fn snapshot_path(pending: &Path) -> PathBuf {
+    pending.with_extension("")
+}
+
+fn pending_path(snapshot: &Path) -> PathBuf {
+    snapshot.with_extension(format!("{SNAPSHOT_EXT}.{PENDING_EXT}"))
+}
+
+pub fn accept(pending: &Path) -> io::Result<()> {
+    fs::rename(pending, snapshot_path(pending))
+}
+
+pub fn reject(pending: &Path) -> io::Result<()> {
+    fs::remove_file(pending)
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Line diff of the changed region between the common prefix and suffix.
+pub fn diff(expected: &str, actual: &str) -> String {
+    let old = expected.lines().collect::<Vec<_>>();
+    let new = actual.lines().collect::<Vec<_>>();
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut out = format!("@@ line {} @@\n", prefix + 1);
+    for line in &old[prefix..old.len() - suffix] {
+        out += &format!("-{line}\n");
+    }
+    for line in &new[prefix..new.len() - suffix] {
+        out += &format!("+{line}\n");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_shows_the_changed_region() {
+        assert_eq!(diff("a\nb\nc\n", "a\nx\nc\n"), "@@ line 2 @@\n-b\n+x\n");
+        assert_eq!(diff("a\nb\n", "a\nb\nc\n"), "@@ line 3 @@\n+c\n");
+        assert_eq!(diff("a\nb\nc\n", "c\n"), "@@ line 1 @@\n-a\n-b\n");
+        assert_eq!(diff("a\nb\nb\nc\n", "a\nb\nc\n"), "@@ line 3 @@\n-b\n");
+    }
+}
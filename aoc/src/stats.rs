@@ -0,0 +1,208 @@
+//! Timing and heap accounting for the phases of a day.
+//!
+//! With the `alloc-stats` feature a counting global allocator is installed and
+//! every [`phase`] reports its wall time, allocation count, allocated bytes and
+//! peak heap usage on stderr; [`day`] reports the same for the whole run.
+//! Without the feature both compile down to plain calls.
+
+#[cfg(feature = "alloc-stats")]
+mod counting {
+    use std::alloc::GlobalAlloc;
+    use std::alloc::Layout;
+    use std::alloc::System;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+
+    pub struct Counting;
+
+    #[global_allocator]
+    static ALLOC: Counting = Counting;
+
+    static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+    static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+    static CURRENT: AtomicUsize = AtomicUsize::new(0);
+    static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+    fn grow(size: usize) {
+        let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(current, Ordering::Relaxed);
+    }
+
+    fn record(size: usize) {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED.fetch_add(size, Ordering::Relaxed);
+        grow(size);
+    }
+
+    unsafe impl GlobalAlloc for Counting {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                record(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc_zeroed(layout);
+            if !ptr.is_null() {
+                record(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new_ptr = System.realloc(ptr, layout, new_size);
+            if !new_ptr.is_null() {
+                ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+                if new_size > layout.size() {
+                    ALLOCATED.fetch_add(new_size - layout.size(), Ordering::Relaxed);
+                    grow(new_size - layout.size());
+                } else {
+                    CURRENT.fetch_sub(layout.size() - new_size, Ordering::Relaxed);
+                }
+            }
+            new_ptr
+        }
+    }
+
+    #[derive(Clone, Copy)]
+    pub struct Counters {
+        pub allocations: usize,
+        pub allocated: usize,
+    }
+
+    pub fn counters() -> Counters {
+        Counters {
+            allocations: ALLOCATIONS.load(Ordering::Relaxed),
+            allocated: ALLOCATED.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Restarts peak tracking at the current heap size, returning the old peak.
+    pub fn reset_peak() -> usize {
+        PEAK.swap(CURRENT.load(Ordering::Relaxed), Ordering::Relaxed)
+    }
+
+    pub fn peak() -> usize {
+        PEAK.load(Ordering::Relaxed)
+    }
+
+    pub fn restore_peak(peak: usize) {
+        PEAK.fetch_max(peak, Ordering::Relaxed);
+    }
+}
+
+#[cfg(feature = "alloc-stats")]
+struct Start {
+    time: std::time::Instant,
+    counters: counting::Counters,
+    outer_peak: usize,
+}
+
+#[cfg(feature = "alloc-stats")]
+impl Start {
+    fn now() -> Self {
+        let outer_peak = counting::reset_peak();
+        Self {
+            time: std::time::Instant::now(),
+            counters: counting::counters(),
+            outer_peak,
+        }
+    }
+
+    fn report(self, phase: &str) {
+        let elapsed = self.time.elapsed();
+        let counters = counting::counters();
+        let peak = counting::peak();
+        counting::restore_peak(self.outer_peak);
+        let day = std::env::current_exe()
+            .ok()
+            .and_then(|x| x.file_stem().map(|x| x.to_string_lossy().into_owned()))
+            .unwrap_or_default();
+        eprintln!(
+            "[{day}] {phase:<8} {:>12?}  allocs {:>9}  allocated {:>11}  peak {:>11}",
+            elapsed,
+            counters.allocations - self.counters.allocations,
+            format_bytes(counters.allocated - self.counters.allocated),
+            format_bytes(peak),
+        );
+    }
+}
+
+#[cfg(feature = "alloc-stats")]
+fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+/// Runs `f` as the named phase of the current day.
+pub fn phase<T>(name: &str, f: impl FnOnce() -> T) -> T {
+    #[cfg(feature = "alloc-stats")]
+    {
+        let start = Start::now();
+        let result = f();
+        start.report(name);
+        result
+    }
+    #[cfg(not(feature = "alloc-stats"))]
+    {
+        let _ = name;
+        f()
+    }
+}
+
+/// Start of the current day, taken by whichever of `DayReport::drop` and
+/// [`exit`] comes first.
+#[cfg(feature = "alloc-stats")]
+static DAY: std::sync::Mutex<Option<Start>> = std::sync::Mutex::new(None);
+
+fn finish() {
+    #[cfg(feature = "alloc-stats")]
+    if let Some(start) = DAY.lock().unwrap().take() {
+        start.report("total");
+    }
+}
+
+/// Reports totals for the whole day when dropped.
+pub struct DayReport {
+    _private: (),
+}
+
+impl Drop for DayReport {
+    fn drop(&mut self) {
+        finish();
+    }
+}
+
+/// Starts the totals of the day, reported when the result is dropped or by
+/// [`exit`].
+pub fn day() -> DayReport {
+    #[cfg(feature = "alloc-stats")]
+    {
+        *DAY.lock().unwrap() = Some(Start::now());
+    }
+    DayReport { _private: () }
+}
+
+/// Reports the totals of the day and exits with `code`. Use this instead of
+/// `process::exit`, which skips dropping the `DayReport`.
+pub fn exit(code: i32) -> ! {
+    finish();
+    std::process::exit(code)
+}
@@ -0,0 +1,379 @@
+//! Terminal dashboard over all days, started with `aoc tui`.
+//!
+//! The dashboard runs every day binary on its input and lists status and
+//! answers. Opening a simulation day records its visualization frames (see
+//! `aoc::viz`) and replays them in a player that can step, play and show the
+//! state details attached to each frame.
+
+use std::env;
+use std::fs;
+use std::io;
+use std::io::Stdout;
+use std::io::Write;
+use std::process;
+use std::process::Command;
+use std::time::Duration;
+use std::time::Instant;
+
+use aoc::days::Day;
+use aoc::days::DAYS;
+use aoc::viz::Frame;
+use crossterm::cursor;
+use crossterm::event;
+use crossterm::event::Event;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEventKind;
+use crossterm::execute;
+use crossterm::queue;
+use crossterm::style;
+use crossterm::style::Color;
+use crossterm::terminal;
+use crossterm::terminal::ClearType;
+
+enum Status {
+    Pending,
+    Ok(Duration),
+    Failed(String),
+}
+
+struct DayRun {
+    day: &'static Day,
+    status: Status,
+    stdout: Vec<String>,
+}
+
+impl DayRun {
+    fn answer(&self) -> &str {
+        self.stdout
+            .iter()
+            .rev()
+            .find(|x| !x.trim().is_empty())
+            .map_or("", |x| x.as_str())
+    }
+}
+
+/// Restores the terminal when dropped, even if drawing fails half way.
+struct Screen {
+    out: Stdout,
+}
+
+impl Screen {
+    fn new() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        let mut out = io::stdout();
+        execute!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(Self { out })
+    }
+
+    fn line(&mut self, row: u16, text: &str, color: Color) -> io::Result<()> {
+        let (width, _) = terminal::size()?;
+        let text = text.chars().take(width as usize).collect::<String>();
+        queue!(
+            self.out,
+            cursor::MoveTo(0, row),
+            style::SetForegroundColor(color),
+            style::Print(text),
+            style::ResetColor,
+            terminal::Clear(ClearType::UntilNewLine)
+        )
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(self.out, cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+fn run_day(day: &Day, envs: &[(&str, String)]) -> Result<(Duration, Vec<String>), String> {
+    let exe = day.executable().map_err(|e| e.to_string())?;
+    if !exe.exists() {
+        return Err(format!("{} not built", exe.display()));
+    }
+    let mut command = Command::new(exe);
+    if day.input().exists() {
+        command.arg(day.input());
+    }
+    let start = Instant::now();
+    let output = command
+        .envs(envs.iter().map(|(k, v)| (k, v)))
+        .output()
+        .map_err(|e| e.to_string())?;
+    let elapsed = start.elapsed();
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr.lines().find(|x| !x.is_empty()).unwrap_or_default();
+        return Err(format!("{}: {reason}", output.status));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::to_string)
+        .collect();
+    Ok((elapsed, stdout))
+}
+
+fn record_day(day: &Day, frame_every: usize) -> Result<Vec<Frame>, String> {
+    let path = env::temp_dir().join(format!("aoc-{}-{}.rec", day.name(), process::id()));
+    let envs = [
+        ("AOC_VIZ", format!("record:{}", path.display())),
+        ("AOC_VIZ_EVERY", frame_every.to_string()),
+    ];
+    let result = run_day(day, &envs);
+    let frames = result.and_then(|_| aoc::viz::read_recording(&path).map_err(|e| e.to_string()));
+    let _ = fs::remove_file(&path);
+    frames
+}
+
+fn refresh(run: &mut DayRun) {
+    match run_day(run.day, &[]) {
+        Ok((elapsed, stdout)) => {
+            run.status = Status::Ok(elapsed);
+            run.stdout = stdout;
+        }
+        Err(e) => {
+            run.status = Status::Failed(e);
+            run.stdout.clear();
+        }
+    }
+}
+
+fn draw_dashboard(screen: &mut Screen, runs: &[DayRun], selected: usize) -> io::Result<()> {
+    let (_, height) = terminal::size()?;
+    queue!(screen.out, terminal::Clear(ClearType::All))?;
+    screen.line(0, "Advent of Code 2022", Color::Yellow)?;
+    for (idx, run) in runs.iter().enumerate() {
+        let (status, color) = match &run.status {
+            Status::Pending => ("...".to_string(), Color::Grey),
+            Status::Ok(elapsed) => (format!("ok {:>7.1?}", elapsed), Color::Green),
+            Status::Failed(_) => ("failed".to_string(), Color::Red),
+        };
+        let marker = if idx == selected { '>' } else { ' ' };
+        let sim = if run.day.frame_every.is_some() {
+            "sim"
+        } else {
+            "   "
+        };
+        let text = format!(
+            "{marker} {:<28} {sim} {status:<12} {}",
+            run.day.name(),
+            run.answer()
+        );
+        screen.line(idx as u16 + 2, &text, color)?;
+    }
+    let run = &runs[selected];
+    let mut row = runs.len() as u16 + 3;
+    screen.line(row, &format!("Output of {}", run.day.name()), Color::Yellow)?;
+    row += 1;
+    let footer = height.saturating_sub(1);
+    let lines = match &run.status {
+        Status::Failed(e) => vec![e.clone()],
+        _ => run.stdout.clone(),
+    };
+    let visible = footer.saturating_sub(row) as usize;
+    for line in lines.iter().skip(lines.len().saturating_sub(visible)) {
+        screen.line(row, line, Color::Reset)?;
+        row += 1;
+    }
+    screen.line(
+        footer,
+        "up/down select  enter play simulation  r rerun  q quit",
+        Color::DarkGrey,
+    )?;
+    screen.out.flush()
+}
+
+struct Player {
+    frames: Vec<Frame>,
+    idx: usize,
+    playing: bool,
+    delay: Duration,
+    inspect: bool,
+    /// Rows hidden below the visible part of frames taller than the screen.
+    scroll: usize,
+}
+
+impl Player {
+    fn step(&mut self, delta: isize) {
+        let last = self.frames.len().saturating_sub(1) as isize;
+        self.idx = (self.idx as isize + delta).clamp(0, last) as usize;
+    }
+
+    /// Rows of the current frame that fit on a screen `height` rows tall.
+    fn frame_rows(&self, height: u16) -> usize {
+        let frame = &self.frames[self.idx];
+        let details = if self.inspect { frame.details.len() } else { 0 };
+        (height.saturating_sub(1) as usize)
+            .saturating_sub(3 + details)
+            .min(frame.height())
+    }
+
+    /// Keeps `scroll` within the rows hidden at the current screen size.
+    fn clamp_scroll(&mut self) -> io::Result<()> {
+        let (_, height) = terminal::size()?;
+        let hidden = self.frames[self.idx].height() - self.frame_rows(height);
+        self.scroll = self.scroll.min(hidden);
+        Ok(())
+    }
+
+    fn draw(&self, screen: &mut Screen, name: &str) -> io::Result<()> {
+        let (width, height) = terminal::size()?;
+        queue!(screen.out, terminal::Clear(ClearType::All))?;
+        let state = if self.playing { "playing" } else { "paused" };
+        let header = format!(
+            "{name}  frame {}/{}  [{state}]  {:?}/frame",
+            self.idx + 1,
+            self.frames.len(),
+            self.delay
+        );
+        screen.line(0, &header, Color::Yellow)?;
+        let frame = &self.frames[self.idx];
+        let footer = height.saturating_sub(1);
+        let frame_rows = self.frame_rows(height);
+        let scroll = self.scroll.min(frame.height() - frame_rows);
+        let top = frame.height() - frame_rows - scroll;
+        for y in 0..frame_rows {
+            queue!(screen.out, cursor::MoveTo(0, y as u16 + 1))?;
+            for x in 0..frame.width().min(width as usize) {
+                let cell = frame.get(x, top + y);
+                let aoc::viz::Rgb(r, g, b) = cell.color;
+                queue!(
+                    screen.out,
+                    style::SetForegroundColor(Color::Rgb { r, g, b }),
+                    style::Print(cell.glyph)
+                )?;
+            }
+            queue!(screen.out, style::ResetColor)?;
+        }
+        let mut row = frame_rows as u16 + 1;
+        screen.line(row, &frame.caption, Color::White)?;
+        row += 1;
+        if self.inspect {
+            for detail in &frame.details {
+                if row >= footer {
+                    break;
+                }
+                screen.line(row, detail, Color::Cyan)?;
+                row += 1;
+            }
+        }
+        screen.line(
+            footer,
+            "left/right step  pgup/pgdn 10  home/end  space play  +/- speed  up/down scroll  i inspect  q back",
+            Color::DarkGrey,
+        )?;
+        screen.out.flush()
+    }
+
+    fn run(mut self, screen: &mut Screen, name: &str) -> io::Result<()> {
+        loop {
+            self.draw(screen, name)?;
+            let timeout = if self.playing {
+                self.delay
+            } else {
+                Duration::from_secs(3600)
+            };
+            if !event::poll(timeout)? {
+                if self.idx + 1 == self.frames.len() {
+                    self.playing = false;
+                } else {
+                    self.step(1);
+                }
+                continue;
+            }
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Left | KeyCode::Char('h') => self.step(-1),
+                KeyCode::Right | KeyCode::Char('l') => self.step(1),
+                KeyCode::Up | KeyCode::Char('k') => self.scroll += 1,
+                KeyCode::Down | KeyCode::Char('j') => self.scroll = self.scroll.saturating_sub(1),
+                KeyCode::PageUp => self.step(-10),
+                KeyCode::PageDown => self.step(10),
+                KeyCode::Home => self.idx = 0,
+                KeyCode::End => self.step(isize::MAX / 2),
+                KeyCode::Char(' ') => self.playing = !self.playing,
+                KeyCode::Char('+') => self.delay = (self.delay / 2).max(Duration::from_millis(1)),
+                KeyCode::Char('-') => self.delay = (self.delay * 2).min(Duration::from_secs(2)),
+                KeyCode::Char('i') => self.inspect = !self.inspect,
+                _ => {}
+            }
+            // the frame, the details shown and the scroll all move the limit
+            self.clamp_scroll()?;
+        }
+    }
+}
+
+fn open(screen: &mut Screen, run: &mut DayRun) -> io::Result<()> {
+    let Some(frame_every) = run.day.frame_every else {
+        return Ok(());
+    };
+    screen.line(
+        0,
+        &format!("Recording {} ...", run.day.name()),
+        Color::Yellow,
+    )?;
+    screen.out.flush()?;
+    match record_day(run.day, frame_every) {
+        Ok(frames) if !frames.is_empty() => Player {
+            frames,
+            idx: 0,
+            playing: false,
+            delay: Duration::from_millis(50),
+            inspect: true,
+            scroll: 0,
+        }
+        .run(screen, &run.day.name()),
+        Ok(_) => {
+            run.status = Status::Failed("no frames recorded".to_string());
+            Ok(())
+        }
+        Err(e) => {
+            run.status = Status::Failed(e);
+            Ok(())
+        }
+    }
+}
+
+pub fn run() -> io::Result<()> {
+    let mut screen = Screen::new()?;
+    let mut runs = DAYS
+        .iter()
+        .map(|day| DayRun {
+            day,
+            status: Status::Pending,
+            stdout: Vec::new(),
+        })
+        .collect::<Vec<_>>();
+    let mut selected = 0;
+    for idx in 0..runs.len() {
+        draw_dashboard(&mut screen, &runs, selected)?;
+        refresh(&mut runs[idx]);
+    }
+    loop {
+        draw_dashboard(&mut screen, &runs, selected)?;
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Up | KeyCode::Char('k') => selected = selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => selected = (selected + 1).min(runs.len() - 1),
+            KeyCode::Char('r') => {
+                runs[selected].status = Status::Pending;
+                draw_dashboard(&mut screen, &runs, selected)?;
+                refresh(&mut runs[selected]);
+            }
+            KeyCode::Enter => open(&mut screen, &mut runs[selected])?,
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,593 @@
+//! Frame rendering for the simulation-style days.
+//!
+//! Solvers describe each state of a simulation as a [`Frame`], a grid of
+//! coloured glyphs with a caption and lines of inspectable state, and hand it
+//! to a [`Recorder`]. The recorder is configured through `AOC_VIZ`, a comma
+//! separated list of backends:
+//!
+//! * `ansi` animates the frames in the terminal (on stderr),
+//! * `ppm:<dir>` writes every frame to `<dir>/frame_00000.ppm`,
+//! * `gif:<file>` writes an animated GIF,
+//! * `record:<file>` stores the frames for [`read_recording`].
+//!
+//! `AOC_VIZ_EVERY=<n>` keeps every n-th frame, `AOC_VIZ_DELAY=<ms>` sets the
+//! delay between frames and `AOC_VIZ_SCALE=<px>` the size of a cell in image
+//! backends. Without `AOC_VIZ` the render closures are never called.
+
+use std::env;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use crate::output::fail;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+    pub const GREY: Rgb = Rgb(128, 128, 128);
+    pub const RED: Rgb = Rgb(255, 64, 64);
+    pub const GREEN: Rgb = Rgb(64, 255, 64);
+    pub const YELLOW: Rgb = Rgb(255, 255, 64);
+    pub const BLUE: Rgb = Rgb(64, 128, 255);
+}
+
+/// A distinct colour for the `idx`-th item of a category.
+pub fn color(idx: usize) -> Rgb {
+    const COLORS: [Rgb; 8] = [
+        Rgb(230, 25, 75),
+        Rgb(60, 180, 75),
+        Rgb(255, 225, 25),
+        Rgb(0, 130, 200),
+        Rgb(245, 130, 48),
+        Rgb(145, 30, 180),
+        Rgb(70, 240, 240),
+        Rgb(240, 50, 230),
+    ];
+    COLORS[idx % COLORS.len()]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub glyph: char,
+    pub color: Rgb,
+}
+
+impl Cell {
+    pub const EMPTY: Cell = Cell {
+        glyph: ' ',
+        color: Rgb::BLACK,
+    };
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+    pub caption: String,
+    pub details: Vec<String>,
+}
+
+impl Frame {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![Cell::EMPTY; width * height],
+            caption: String::new(),
+            details: Vec::new(),
+        }
+    }
+
+    pub fn with_caption(mut self, caption: impl Into<String>) -> Self {
+        self.caption = caption.into();
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Cell {
+        if x < self.width && y < self.height {
+            self.cells[y * self.width + x]
+        } else {
+            Cell::EMPTY
+        }
+    }
+
+    /// Sets a single cell, ignoring positions outside the frame.
+    pub fn set(&mut self, x: usize, y: usize, glyph: char, color: Rgb) {
+        if x < self.width && y < self.height {
+            self.cells[y * self.width + x] = Cell { glyph, color };
+        }
+    }
+
+    /// Writes `text` starting at `(x, y)`, clipped at the right edge.
+    pub fn text(&mut self, x: usize, y: usize, text: &str, color: Rgb) {
+        for (idx, glyph) in text.chars().enumerate() {
+            self.set(x + idx, y, glyph, color);
+        }
+    }
+}
+
+pub trait Backend {
+    fn draw(&mut self, frame: &Frame) -> io::Result<()>;
+
+    fn finish(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+pub struct Ansi {
+    delay: Duration,
+    cleared: bool,
+}
+
+impl Ansi {
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            cleared: false,
+        }
+    }
+}
+
+impl Backend for Ansi {
+    fn draw(&mut self, frame: &Frame) -> io::Result<()> {
+        let mut out = String::new();
+        if !self.cleared {
+            out += "\x1b[2J";
+            self.cleared = true;
+        }
+        out += "\x1b[H";
+        for y in 0..frame.height() {
+            let mut current = None;
+            for x in 0..frame.width() {
+                let Cell { glyph, color } = frame.get(x, y);
+                if glyph != ' ' && current != Some(color) {
+                    out += &format!("\x1b[38;2;{};{};{}m", color.0, color.1, color.2);
+                    current = Some(color);
+                }
+                out.push(glyph);
+            }
+            out += "\x1b[0m\x1b[K\n";
+        }
+        out += &format!("{}\x1b[K\n", frame.caption);
+        let mut stderr = io::stderr().lock();
+        stderr.write_all(out.as_bytes())?;
+        stderr.flush()?;
+        thread::sleep(self.delay);
+        Ok(())
+    }
+}
+
+fn pixels(frame: &Frame, scale: usize, width: usize, height: usize) -> Vec<Rgb> {
+    let mut pixels = Vec::with_capacity(width * height * scale * scale);
+    for y in 0..height * scale {
+        for x in 0..width * scale {
+            let cell = frame.get(x / scale, y / scale);
+            pixels.push(if cell.glyph == ' ' {
+                Rgb::BLACK
+            } else {
+                cell.color
+            });
+        }
+    }
+    pixels
+}
+
+pub struct Ppm {
+    dir: PathBuf,
+    scale: usize,
+    count: usize,
+}
+
+impl Ppm {
+    pub fn new(dir: impl Into<PathBuf>, scale: usize) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            scale,
+            count: 0,
+        })
+    }
+}
+
+impl Backend for Ppm {
+    fn draw(&mut self, frame: &Frame) -> io::Result<()> {
+        let path = self.dir.join(format!("frame_{:05}.ppm", self.count));
+        self.count += 1;
+        let mut out = BufWriter::new(File::create(path)?);
+        let (width, height) = (frame.width() * self.scale, frame.height() * self.scale);
+        write!(out, "P6\n{width} {height}\n255\n")?;
+        for Rgb(r, g, b) in pixels(frame, self.scale, frame.width(), frame.height()) {
+            out.write_all(&[r, g, b])?;
+        }
+        out.flush()
+    }
+}
+
+/// Animated GIF; the canvas size is fixed by the first frame.
+pub struct Gif {
+    path: PathBuf,
+    scale: usize,
+    delay: Duration,
+    encoder: Option<(gif::Encoder<BufWriter<File>>, usize, usize)>,
+}
+
+impl Gif {
+    pub fn new(path: impl Into<PathBuf>, scale: usize, delay: Duration) -> Self {
+        Self {
+            path: path.into(),
+            scale,
+            delay,
+            encoder: None,
+        }
+    }
+}
+
+fn gif_error(e: gif::EncodingError) -> io::Error {
+    io::Error::other(e)
+}
+
+/// Indexes into the 6x6x6 colour cube used as the global GIF palette.
+fn palette_index(Rgb(r, g, b): Rgb) -> u8 {
+    let level = |x: u8| (x as u16 * 5 + 127) / 255;
+    (level(r) * 36 + level(g) * 6 + level(b)) as u8
+}
+
+fn palette() -> Vec<u8> {
+    let mut palette = Vec::with_capacity(216 * 3);
+    for r in 0..6u8 {
+        for g in 0..6u8 {
+            for b in 0..6u8 {
+                palette.extend([r * 51, g * 51, b * 51]);
+            }
+        }
+    }
+    palette
+}
+
+/// Size in pixels of `cells` cells at `scale`, if a GIF can hold it.
+fn gif_size(cells: usize, scale: usize) -> io::Result<u16> {
+    cells
+        .checked_mul(scale)
+        .and_then(|x| u16::try_from(x).ok())
+        .ok_or_else(|| {
+            io::Error::other(format!(
+                "{cells} cells at scale {scale} exceed the GIF limit of {} pixels",
+                u16::MAX
+            ))
+        })
+}
+
+impl Backend for Gif {
+    fn draw(&mut self, frame: &Frame) -> io::Result<()> {
+        if self.encoder.is_none() {
+            let (width, height) = (frame.width(), frame.height());
+            let (pixel_width, pixel_height) =
+                (gif_size(width, self.scale)?, gif_size(height, self.scale)?);
+            let out = BufWriter::new(File::create(&self.path)?);
+            let mut encoder =
+                gif::Encoder::new(out, pixel_width, pixel_height, &palette()).map_err(gif_error)?;
+            encoder
+                .set_repeat(gif::Repeat::Infinite)
+                .map_err(gif_error)?;
+            self.encoder = Some((encoder, width, height));
+        }
+        let (encoder, width, height) = self.encoder.as_mut().unwrap();
+        let indices = pixels(frame, self.scale, *width, *height)
+            .into_iter()
+            .map(palette_index)
+            .collect::<Vec<_>>();
+        let mut gif_frame = gif::Frame::from_indexed_pixels(
+            gif_size(*width, self.scale)?,
+            gif_size(*height, self.scale)?,
+            indices,
+            None,
+        );
+        gif_frame.delay = (self.delay.as_millis() / 10) as u16;
+        encoder.write_frame(&gif_frame).map_err(gif_error)
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        if let Some((encoder, _, _)) = self.encoder.take() {
+            encoder.into_inner().map_err(gif_error)?.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Stores frames in a line based text format, see [`read_recording`].
+pub struct Record {
+    out: BufWriter<File>,
+}
+
+impl Record {
+    pub fn new(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self {
+            out: BufWriter::new(File::create(path)?),
+        })
+    }
+}
+
+fn hex(Rgb(r, g, b): Rgb) -> String {
+    format!("{r:02x}{g:02x}{b:02x}")
+}
+
+fn parse_hex(s: &str) -> Option<Rgb> {
+    let value = u32::from_str_radix(s, 16).ok()?;
+    Some(Rgb((value >> 16) as u8, (value >> 8) as u8, value as u8))
+}
+
+impl Backend for Record {
+    fn draw(&mut self, frame: &Frame) -> io::Result<()> {
+        writeln!(self.out, "frame {} {}", frame.width(), frame.height())?;
+        writeln!(self.out, "caption {}", frame.caption)?;
+        for detail in &frame.details {
+            writeln!(self.out, "detail {detail}")?;
+        }
+        for y in 0..frame.height() {
+            let row = (0..frame.width())
+                .map(|x| frame.get(x, y))
+                .collect::<Vec<_>>();
+            let glyphs = row.iter().map(|x| x.glyph).collect::<String>();
+            let mut runs = Vec::<(Rgb, usize)>::new();
+            for cell in &row {
+                match runs.last_mut() {
+                    Some((color, count)) if *color == cell.color => *count += 1,
+                    _ => runs.push((cell.color, 1)),
+                }
+            }
+            let runs = runs
+                .iter()
+                .map(|(color, count)| format!("{}*{count}", hex(*color)))
+                .collect::<Vec<_>>()
+                .join(" ");
+            writeln!(self.out, "g {glyphs}")?;
+            writeln!(self.out, "c {runs}")?;
+        }
+        writeln!(self.out, "end")
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// Loads the frames written by the `record` backend.
+pub fn read_recording(path: impl AsRef<Path>) -> io::Result<Vec<Frame>> {
+    let invalid = |line: &str| io::Error::new(io::ErrorKind::InvalidData, format!("{line:?}"));
+    let mut frames = Vec::new();
+    let mut frame = None::<Frame>;
+    let mut y = 0;
+    for line in BufReader::new(File::open(path)?).lines() {
+        let line = line?;
+        let (tag, rest) = line.split_once(' ').unwrap_or((&line, ""));
+        match (tag, frame.as_mut()) {
+            ("frame", None) => {
+                let (width, height) = rest.split_once(' ').ok_or_else(|| invalid(&line))?;
+                let width = width.parse().map_err(|_| invalid(&line))?;
+                let height = height.parse().map_err(|_| invalid(&line))?;
+                frame = Some(Frame::new(width, height));
+                y = 0;
+            }
+            ("caption", Some(frame)) => frame.caption = rest.to_string(),
+            ("detail", Some(frame)) => frame.details.push(rest.to_string()),
+            ("g", Some(frame)) => {
+                for (x, glyph) in rest.chars().enumerate() {
+                    frame.set(x, y, glyph, Rgb::BLACK);
+                }
+            }
+            ("c", Some(frame)) => {
+                let mut x = 0;
+                for run in rest.split_whitespace() {
+                    let (color, count) = run.split_once('*').ok_or_else(|| invalid(&line))?;
+                    let color = parse_hex(color).ok_or_else(|| invalid(&line))?;
+                    let count = count.parse::<usize>().map_err(|_| invalid(&line))?;
+                    for x in x..x + count {
+                        let glyph = frame.get(x, y).glyph;
+                        frame.set(x, y, glyph, color);
+                    }
+                    x += count;
+                }
+                y += 1;
+            }
+            ("end", Some(_)) => frames.extend(frame.take()),
+            _ => return Err(invalid(&line)),
+        }
+    }
+    Ok(frames)
+}
+
+fn env_number(name: &str, default: u64) -> Result<u64, String> {
+    match env::var(name) {
+        Ok(value) => value
+            .parse()
+            .map_err(|_| format!("{name} must be a number, got {value:?}")),
+        Err(_) => Ok(default),
+    }
+}
+
+/// Parses a backend list such as `ansi,gif:out.gif`.
+pub fn parse_backends(
+    spec: &str,
+    delay: Duration,
+    scale: usize,
+) -> Result<Vec<Box<dyn Backend>>, String> {
+    let mut backends = Vec::<Box<dyn Backend>>::new();
+    for item in spec.split(',').map(str::trim).filter(|x| !x.is_empty()) {
+        match item.split_once(':') {
+            None if item == "ansi" => backends.push(Box::new(Ansi::new(delay))),
+            Some(("ppm", dir)) => backends.push(Box::new(
+                Ppm::new(dir, scale).map_err(|e| format!("{dir}: {e}"))?,
+            )),
+            Some(("gif", path)) => backends.push(Box::new(Gif::new(path, scale, delay))),
+            Some(("record", path)) => backends.push(Box::new(
+                Record::new(path).map_err(|e| format!("{path}: {e}"))?,
+            )),
+            _ => return Err(format!("Unknown visualization backend {item:?}")),
+        }
+    }
+    Ok(backends)
+}
+
+pub struct Recorder {
+    backends: Vec<Box<dyn Backend>>,
+    every: usize,
+    count: usize,
+}
+
+impl Recorder {
+    pub fn new(backends: Vec<Box<dyn Backend>>, every: usize) -> Self {
+        Self {
+            backends,
+            every: every.max(1),
+            count: 0,
+        }
+    }
+
+    pub fn disabled() -> Self {
+        Self::new(vec![], 1)
+    }
+
+    pub fn from_env() -> Self {
+        let Ok(spec) = env::var("AOC_VIZ") else {
+            return Self::disabled();
+        };
+        let number = |name, default| env_number(name, default).unwrap_or_else(|e| fail(e));
+        let delay = Duration::from_millis(number("AOC_VIZ_DELAY", 50));
+        let scale = number("AOC_VIZ_SCALE", 4) as usize;
+        let every = number("AOC_VIZ_EVERY", 1) as usize;
+        let backends = parse_backends(&spec, delay, scale).unwrap_or_else(|e| fail(e));
+        Self::new(backends, every)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.backends.is_empty()
+    }
+
+    /// Offers the next frame; `render` only runs if the frame is kept.
+    pub fn emit(&mut self, render: impl FnOnce() -> Frame) {
+        if !self.is_enabled() {
+            return;
+        }
+        let idx = self.count;
+        self.count += 1;
+        if !idx.is_multiple_of(self.every) {
+            return;
+        }
+        let frame = render();
+        for backend in &mut self.backends {
+            if let Err(e) = backend.draw(&frame) {
+                fail(format!("visualization: {e}"));
+            }
+        }
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        for backend in &mut self.backends {
+            if let Err(e) = backend.finish() {
+                fail(format!("visualization: {e}"));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn palette_index_picks_the_nearest_cube_colour() {
+        assert_eq!(palette_index(Rgb::BLACK), 0);
+        assert_eq!(palette_index(Rgb::WHITE), 215);
+        assert_eq!(palette_index(Rgb(255, 0, 0)), 180);
+        assert_eq!(palette_index(Rgb(0, 0, 25)), 0);
+        assert_eq!(palette_index(Rgb(0, 0, 26)), 1);
+        let palette = palette();
+        for color in [Rgb::GREY, Rgb::RED, Rgb::BLUE, color(3)] {
+            let idx = palette_index(color) as usize * 3;
+            let entry = &palette[idx..idx + 3];
+            for (channel, value) in [color.0, color.1, color.2].into_iter().zip(entry) {
+                assert!(channel.abs_diff(*value) <= 25, "{color:?} -> {entry:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn parse_backends_accepts_lists() {
+        let delay = Duration::ZERO;
+        assert_eq!(parse_backends("", delay, 1).unwrap().len(), 0);
+        assert_eq!(parse_backends(" ansi , ,ansi", delay, 1).unwrap().len(), 2);
+        let dir = env::temp_dir().join(format!("aoc-viz-test-{}", std::process::id()));
+        let spec = format!(
+            "ppm:{},gif:{},record:{}",
+            dir.join("ppm").display(),
+            dir.join("out.gif").display(),
+            dir.join("out.rec").display()
+        );
+        fs::create_dir_all(&dir).unwrap();
+        assert_eq!(parse_backends(&spec, delay, 1).unwrap().len(), 3);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn parse_backends_rejects_unknown_items() {
+        let error = |spec| parse_backends(spec, Duration::ZERO, 1).err().unwrap();
+        assert_eq!(error("ansi,svg"), "Unknown visualization backend \"svg\"");
+        assert_eq!(error("ansi:x"), "Unknown visualization backend \"ansi:x\"");
+        assert_eq!(error("gif"), "Unknown visualization backend \"gif\"");
+    }
+
+    #[test]
+    fn recordings_read_back_to_the_same_frames() {
+        let mut first = Frame::new(6, 3).with_caption("step 1 of 2");
+        first.text(0, 0, "ab  cd", Rgb::RED);
+        first.set(3, 0, ' ', Rgb::BLUE);
+        first.text(1, 1, "x y", Rgb::WHITE);
+        first.set(5, 2, '#', color(4));
+        first.details = vec!["stack 1: A B".to_string(), "  indented  ".to_string()];
+        let mut second = Frame::new(2, 1);
+        second.text(0, 0, " ", Rgb::GREY);
+        let frames = vec![first, second, Frame::new(0, 0)];
+
+        let path = env::temp_dir().join(format!("aoc-viz-test-{}.rec", std::process::id()));
+        let mut record = Record::new(&path).unwrap();
+        for frame in &frames {
+            record.draw(frame).unwrap();
+        }
+        record.finish().unwrap();
+        drop(record);
+        let read = read_recording(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(read.unwrap(), frames);
+    }
+
+    #[test]
+    fn gif_size_is_checked() {
+        assert_eq!(gif_size(100, 4).unwrap(), 400);
+        assert_eq!(gif_size(65535, 1).unwrap(), u16::MAX);
+        assert!(gif_size(16384, 4).is_err());
+        assert!(gif_size(usize::MAX, 2).is_err());
+    }
+}